@@ -5,7 +5,10 @@ use rand_distr::{Normal, Uniform, WeightedIndex};
 use serde::Deserialize;
 use std::cell::RefCell;
 
-use super::organism::animal::Diet;
+use super::{
+    organism::animal::Diet,
+    terrain::{tile::TileShape, BiomeType},
+};
 
 pub struct ConfigPlugin;
 
@@ -95,6 +98,13 @@ fn load_config_for_native() -> SimulationConfig {
         .try_deserialize::<EnvironmentConfig>()
         .expect("Can't deserialize environment config to config struct!");
 
+    let disease_config = Config::builder()
+        .add_source(config::File::with_name("config/disease.yaml"))
+        .build()
+        .expect("Can't read disease configuration!")
+        .try_deserialize::<DiseaseConfig>()
+        .expect("Can't deserialize disease config to config struct!");
+
     let data_collection_config = Config::builder()
         .add_source(config::File::with_name("config/data_collection.yaml"))
         .build()
@@ -117,6 +127,7 @@ fn load_config_for_native() -> SimulationConfig {
         terrain: terrain_config,
         time: time_config,
         environment: environment_config,
+        disease: disease_config,
         data_collection: data_collection_config,
         window: window_config,
     }
@@ -126,9 +137,17 @@ fn load_config_for_native() -> SimulationConfig {
 fn load_config_for_wasm() -> SimulationConfig {
     let organism_config = OrganismConfig {
         max_health_gene_config: FloatGeneConfig::new(200.0, 0.0),
+        regeneration_gene_config: FloatGeneConfig::new(2.0, 0.0),
         max_active_energy_gene_config: FloatGeneConfig::new(1000.0, 0.0),
-        reproduction_energy_cost_gene_config: FloatGeneConfig::new(200.0, 0.0),
         age_penalty_gene_config: FloatGeneConfig::new(0.8, 0.2),
+        lifespan_gene_config: IntGeneConfig::new(240, 720),
+        senescence_onset_gene_config: IntGeneConfig::new(96, 480),
+        senescence_rate_gene_config: FloatGeneConfig::new(0.01, 0.0),
+        max_reproduction_count_gene_config: IntGeneConfig::new(1, 10),
+        optimal_temperature_gene_config: FloatGeneConfig::new(100.0, 0.0)
+            .with_starting_expression(0.15),
+        temperature_tolerance_gene_config: FloatGeneConfig::new(30.0, 0.0),
+        min_temperature_tolerance: 1.0,
         starting_age_dist: DiscreteDistribution::Range { min: 1, max: 24 },
         starting_mass_dist: ContinuousDistribution::Normal {
             mean: 10.0,
@@ -139,6 +158,11 @@ fn load_config_for_wasm() -> SimulationConfig {
         offspring_spawn_range: 100.0,
         max_energy_consumption_per_mass_unit: 9.0,
         carcass_mass_decay_percentage: 0.1,
+        species_distance_threshold: 0.1,
+        min_new_species_population: 5,
+        regeneration_energy_cost: 20.0,
+        max_injury_penalty: 0.5,
+        post_reproductive_stage: true,
     };
 
     let animal_config = AnimalConfig {
@@ -159,10 +183,23 @@ fn load_config_for_wasm() -> SimulationConfig {
         sight_range_gene_config: FloatGeneConfig::new(300.0, 0.0),
         action_range_gene_config: FloatGeneConfig::new(20.0, 0.0),
         attack_damage_gene_config: FloatGeneConfig::new(5.0, 0.0),
+        male_attack_damage_gene_config: FloatGeneConfig::new(7.0, 0.0),
+        bite_size_gene_config: FloatGeneConfig::new(2.0, 0.0),
+        toxin_tolerance_gene_config: FloatGeneConfig::new(1.0, 0.0).with_starting_expression(0.3),
         energy_to_survive_per_mass_unit_gene_config: FloatGeneConfig::new(1.0, 0.2),
+        reproduction_energy_cost_gene_config: FloatGeneConfig::new(2000.0, 0.0),
         do_nothing_for_hours: 2,
+        diet_digestion_threshold: 0.5,
+        offspring_mass_energy_ratio: 0.5,
+        parental_care: false,
+        parental_feeding_energy: 50.0,
         reproduction_cooldown_gene_config: IntGeneConfig::new(6, 18),
         maturity_age_gene_config: IntGeneConfig::new(12, 12),
+        plant_digestion_gene_config: FloatGeneConfig::new(1.0, 0.0).with_starting_expression(0.9),
+        meat_digestion_gene_config: FloatGeneConfig::new(1.0, 0.0).with_starting_expression(0.1),
+        gestation_period_gene_config: IntGeneConfig::new(12, 24),
+        litter_size_gene_config: IntGeneConfig::new(1, 3),
+        care_duration_gene_config: IntGeneConfig::new(0, 24),
     };
 
     let plant_config = PlantConfig {
@@ -170,20 +207,63 @@ fn load_config_for_wasm() -> SimulationConfig {
         nutrient_consumption_gene_config: FloatGeneConfig::new(2.0, 0.0),
         pollination_range_gene_config: FloatGeneConfig::new(1000.0, 0.0),
         energy_to_survive_per_mass_unit_gene_config: FloatGeneConfig::new(4.0, 1.0),
+        reproduction_energy_cost_gene_config: FloatGeneConfig::new(2000.0, 0.0),
         group_spawn_on_grass_chance: BooleanDistribution::Chance { chance: 0.2 },
         group_size_dist: DiscreteDistribution::Range { min: 8, max: 16 },
         reproduction_cooldown_gene_config: IntGeneConfig::new(6, 18),
         maturity_age_gene_config: IntGeneConfig::new(6, 6),
+        separate_sexes: false,
+        seed_dispersal_distance_gene_config: FloatGeneConfig::new(200.0, 0.0),
+        seed_dormancy_time: 12,
+        seed_viability_time: 96,
+        seed_mass_energy_ratio: 0.5,
+        germination_min_humidity: 0.2,
+        germination_min_nutrients: 1.0,
+        animal_seed_dispersal_chance: 0.05,
+        seed_carrying_time: 12,
+        height_gene_config: FloatGeneConfig::new(2.0, 0.0),
+        height_maintenance_cost: 5.0,
+        light_extinction_coefficient: 0.15,
+        min_mass: 2.0,
+        toxicity_gene_config: FloatGeneConfig::new(1.0, 0.0).with_starting_expression(0.4),
+        toxin_production_cost: 20.0,
+        toxin_damage_per_mass_unit: 5.0,
     };
 
     let terrain_config = TerrainConfig {
         map_width: 50,
         map_height: 50,
         tile_size: 100.0,
+        tile_shape: TileShape::Square,
+        toroidal: false,
         thermal_overlay_update_cooldown: 1.0,
         biome_overlay_update_cooldown: 60.0,
-        nutrients_per_tile_dirt: 4.0,
-        nutrients_per_tile_sand: 2.0,
+        overlay_update_cooldown: 1.0,
+        terrain_file: None,
+        starting_temperature: 15.0,
+        heat_loss_per_degree: 50.0,
+        biomes: BiomesConfig {
+            stone: BiomeConfig::new(0.0, 0.0, 800.0, 30.0),
+            sand: BiomeConfig::new(-2.0, 0.0, 600.0, 15.0),
+            dirt: BiomeConfig::new(4.0, 1.0, 1000.0, 20.0),
+            grass: BiomeConfig::new(6.0, 1.2, 1200.0, 20.0),
+            water: BiomeConfig::new(0.0, 0.0, 4000.0, 60.0),
+            river: BiomeConfig::new(0.0, 0.0, 4000.0, 60.0),
+        },
+        rivers: RiversConfig {
+            count: 1,
+            max_length: 50,
+            source_min_elevation: 0.3,
+        },
+        succession: SuccessionConfig {
+            grass_min_plants: 6,
+            grass_min_humidity: 0.3,
+            dirt_max_plants: 2,
+            sand_max_humidity: 0.1,
+            days_to_grass: 3,
+            days_to_dirt: 3,
+            days_to_sand: 10,
+        },
     };
 
     let time_config = TimeConfig {
@@ -195,18 +275,32 @@ fn load_config_for_wasm() -> SimulationConfig {
     let environment_config = EnvironmentConfig {
         starting_hour: 0,
         sun_energy_output_per_tile: 1000.0,
-        sun_energy_output_per_plant: 100.0,
+        sun_light_per_tile: 300.0,
         sun_day_energy_ratio: 1.0,
         sun_night_energy_ratio: 0.2,
         water_humidity: 1.0,
         humidity_spread_coefficient: 0.9,
     };
 
+    let disease_config = DiseaseConfig {
+        initial_infection_chance: 0.05,
+        contact_transmission_chance: 0.05,
+        predation_transmission_chance: 0.3,
+        carcass_transmission_chance: 0.2,
+        incubation_time: 24,
+        infection_duration: 48,
+        energy_drain: 20.0,
+        mortality: 0.2,
+        resistance_gene_config: FloatGeneConfig::new(1.0, 0.0),
+    };
+
     // NOTE: won't be used on the web anyway
     let data_collection_config = DataCollectionConfig {
         directory: "data".into(),
         plants_filename: "plants.msgpack".into(),
         animals_filename: "animals.msgpack".into(),
+        speciation_filename: "speciation.msgpack".into(),
+        lineage_filename: "lineage.msgpack".into(),
     };
 
     // NOTE: won't be used on the web anyway
@@ -224,6 +318,7 @@ fn load_config_for_wasm() -> SimulationConfig {
         terrain: terrain_config,
         time: time_config,
         environment: environment_config,
+        disease: disease_config,
         data_collection: data_collection_config,
         window: window_config,
     }
@@ -238,6 +333,7 @@ pub struct SimulationConfig {
     pub terrain: TerrainConfig,
     pub time: TimeConfig,
     pub environment: EnvironmentConfig,
+    pub disease: DiseaseConfig,
     pub data_collection: DataCollectionConfig,
     pub window: WindowConfig,
 }
@@ -245,15 +341,41 @@ pub struct SimulationConfig {
 #[derive(Debug, Deserialize)]
 pub struct OrganismConfig {
     pub max_health_gene_config: FloatGeneConfig,
+    /// hp regenerated per time unit
+    pub regeneration_gene_config: FloatGeneConfig,
     pub max_active_energy_gene_config: FloatGeneConfig,
-    pub reproduction_energy_cost_gene_config: FloatGeneConfig,
     pub age_penalty_gene_config: FloatGeneConfig,
+    /// maximum age, in time units
+    pub lifespan_gene_config: IntGeneConfig,
+    /// age at which a trait starts to weaken, in time units
+    pub senescence_onset_gene_config: IntGeneConfig,
+    /// part of trait value lost every time unit after onset
+    pub senescence_rate_gene_config: FloatGeneConfig,
+    /// number of reproductions during the whole life
+    pub max_reproduction_count_gene_config: IntGeneConfig,
+    pub optimal_temperature_gene_config: FloatGeneConfig,
+    pub temperature_tolerance_gene_config: FloatGeneConfig,
+    /// smallest distance from optimal temperature at which thermal stress equals `1.0`, whatever the gene says
+    pub min_temperature_tolerance: f32,
 
     pub starting_age_dist: DiscreteDistribution,
     pub starting_mass_dist: ContinuousDistribution,
 
     pub offspring_spawn_range: f32,
     pub carcass_mass_decay_percentage: f32,
+
+    /// Organisms with genomes further apart than this are put in different species
+    pub species_distance_threshold: f32,
+    /// Smaller groups of diverged organisms stay in their species
+    pub min_new_species_population: usize,
+
+    /// active energy needed to regenerate one hp
+    pub regeneration_energy_cost: f32,
+    /// how much an organism with all hp missing because of injuries is slowed down and weakened, from `0.0` to `1.0`
+    pub max_injury_penalty: f32,
+
+    /// organisms out of reproductions keep living, otherwise they die after their last reproduction
+    pub post_reproductive_stage: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -263,6 +385,14 @@ pub struct AnimalConfig {
     pub size_dist: ContinuousDistribution,
     pub diet_dist: DiscreteDistribution,
     pub do_nothing_for_hours: u32,
+    /// animal eats plants/meat if its digestion efficiency of them is at least this high
+    pub diet_digestion_threshold: f32,
+    /// part of energy invested by the mother into each offspring that becomes its body mass, the rest is its active energy
+    pub offspring_mass_energy_ratio: f32,
+    /// juveniles follow their mother, who feeds and defends them, for as long as her `care_duration_gene` says
+    pub parental_care: bool,
+    /// energy given by the mother to her hungry juvenile every time unit
+    pub parental_feeding_energy: f32,
 }
 
 #[derive(Debug)]
@@ -288,9 +418,24 @@ pub struct AnimalSpeciesConfig {
     pub sight_range_gene_config: FloatGeneConfig,
     pub action_range_gene_config: FloatGeneConfig,
     pub attack_damage_gene_config: FloatGeneConfig,
+    /// Expressed only in males instead of `attack_damage_gene_config`
+    pub male_attack_damage_gene_config: FloatGeneConfig,
+    /// mass of food (live plant, seed or carcass) eaten in one time unit
+    pub bite_size_gene_config: FloatGeneConfig,
+    /// part of plant toxicity neutralized when grazing
+    pub toxin_tolerance_gene_config: FloatGeneConfig,
     pub energy_to_survive_per_mass_unit_gene_config: FloatGeneConfig,
+    /// energy invested by the mother into each offspring
+    pub reproduction_energy_cost_gene_config: FloatGeneConfig,
     pub reproduction_cooldown_gene_config: IntGeneConfig,
     pub maturity_age_gene_config: IntGeneConfig,
+    pub plant_digestion_gene_config: FloatGeneConfig,
+    pub meat_digestion_gene_config: FloatGeneConfig,
+    /// in time units
+    pub gestation_period_gene_config: IntGeneConfig,
+    pub litter_size_gene_config: IntGeneConfig,
+    /// in time units
+    pub care_duration_gene_config: IntGeneConfig,
 }
 
 #[derive(Debug, Deserialize)]
@@ -299,11 +444,43 @@ pub struct PlantConfig {
     pub nutrient_consumption_gene_config: FloatGeneConfig,
     pub pollination_range_gene_config: FloatGeneConfig,
     pub energy_to_survive_per_mass_unit_gene_config: FloatGeneConfig,
+    /// energy invested by the mother plant into each seed
+    pub reproduction_energy_cost_gene_config: FloatGeneConfig,
 
     pub group_spawn_on_grass_chance: BooleanDistribution,
     pub group_size_dist: DiscreteDistribution,
     pub reproduction_cooldown_gene_config: IntGeneConfig,
     pub maturity_age_gene_config: IntGeneConfig,
+    /// Plants are either female or male instead of hermaphroditic, only females spread offspring
+    pub separate_sexes: bool,
+
+    /// how far from the mother plant its seeds land
+    pub seed_dispersal_distance_gene_config: FloatGeneConfig,
+    /// time units before seed can germinate
+    pub seed_dormancy_time: u32,
+    /// time units after which seed that hasn't germinated dies
+    pub seed_viability_time: u32,
+    /// part of energy invested by the mother into each seed that becomes its mass, the rest is its active energy
+    pub seed_mass_energy_ratio: f32,
+    pub germination_min_humidity: f32,
+    pub germination_min_nutrients: f32,
+    /// chance per time unit of a seed sticking to an animal on the same tile, `0.0` disables dispersal by animals
+    pub animal_seed_dispersal_chance: f32,
+    /// time units before seed falls off the animal
+    pub seed_carrying_time: u32,
+    pub height_gene_config: FloatGeneConfig,
+    /// energy per unit of height paid every time unit
+    pub height_maintenance_cost: f32,
+    /// how much light a unit of leaf area absorbs, following the Beer-Lambert law
+    pub light_extinction_coefficient: f32,
+    /// plants grazed below this mass die, otherwise they regrow
+    pub min_mass: f32,
+    /// from `0.0` (harmless) to `1.0` (herbivores get no energy from the plant)
+    pub toxicity_gene_config: FloatGeneConfig,
+    /// energy per unit of toxicity paid every time unit
+    pub toxin_production_cost: f32,
+    /// damage dealt to herbivore per grazed mass unit at full effective toxicity
+    pub toxin_damage_per_mass_unit: f32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -311,12 +488,104 @@ pub struct TerrainConfig {
     pub map_width: u32,
     pub map_height: u32,
     pub tile_size: f32,
+    pub tile_shape: TileShape,
+    /// connect opposite map edges, so there are no borders
+    pub toroidal: bool,
 
     pub thermal_overlay_update_cooldown: f32,
     pub biome_overlay_update_cooldown: f32,
+    /// seconds between repaints of humidity, nutrients, biomass, density and carcass overlays
+    pub overlay_update_cooldown: f32,
+
+    /// terrain saved in the terrain editor, if set it's loaded instead of generating a new one
+    pub terrain_file: Option<String>,
+
+    /// temperature every tile has when the terrain is generated
+    pub starting_temperature: f32,
+    /// heat radiated away by a tile every time unit for every degree of its temperature
+    pub heat_loss_per_degree: f32,
+
+    pub biomes: BiomesConfig,
+    pub succession: SuccessionConfig,
+    pub rivers: RiversConfig,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BiomesConfig {
+    pub stone: BiomeConfig,
+    pub sand: BiomeConfig,
+    pub dirt: BiomeConfig,
+    pub grass: BiomeConfig,
+    pub water: BiomeConfig,
+    pub river: BiomeConfig,
+}
 
-    pub nutrients_per_tile_dirt: f32,
-    pub nutrients_per_tile_sand: f32,
+impl BiomesConfig {
+    pub fn get(&self, biome: &BiomeType) -> &BiomeConfig {
+        match *biome {
+            BiomeType::Stone => &self.stone,
+            BiomeType::Sand => &self.sand,
+            BiomeType::Dirt => &self.dirt,
+            BiomeType::Grass => &self.grass,
+            BiomeType::Water => &self.water,
+            BiomeType::River => &self.river,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BiomeConfig {
+    /// nutrients restored on the tile every time unit, negative values drain plants instead of feeding them
+    pub nutrients: f32,
+    /// multiplier of energy plants produce on this biome
+    pub plant_suitability: f32,
+    /// heat needed to change temperature of the tile by 1 degree, high values make the tile buffer temperature changes
+    pub heat_capacity: f32,
+    /// how fast heat flows between this tile and its neighbours
+    pub thermal_conductivity: f32,
+}
+
+impl BiomeConfig {
+    pub fn new(
+        nutrients: f32,
+        plant_suitability: f32,
+        heat_capacity: f32,
+        thermal_conductivity: f32,
+    ) -> Self {
+        Self {
+            nutrients,
+            plant_suitability,
+            heat_capacity,
+            thermal_conductivity,
+        }
+    }
+}
+
+/// Rules for tiles changing their biome over time. Everything is checked once per day.
+#[derive(Debug, Deserialize)]
+pub struct SuccessionConfig {
+    /// Dirt becomes Grass if there are at least that many plants on the tile
+    pub grass_min_plants: usize,
+    pub grass_min_humidity: f32,
+    /// Grass becomes Dirt again (overgrazing) if there are at most that many plants on the tile
+    pub dirt_max_plants: usize,
+    /// Dirt and Grass become Sand if humidity stays below that value
+    pub sand_max_humidity: f32,
+
+    pub days_to_grass: u32,
+    pub days_to_dirt: u32,
+    pub days_to_sand: u32,
+}
+
+/// Rivers are traced downhill after the terrain is generated
+#[derive(Debug, Deserialize)]
+pub struct RiversConfig {
+    /// maximum number of rivers, there can be less if there are not enough high tiles
+    pub count: usize,
+    /// maximum number of tiles in one river
+    pub max_length: usize,
+    /// rivers start only on tiles with elevation at least that high (elevation is in range [-1; 1])
+    pub source_min_elevation: f32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -330,7 +599,8 @@ pub struct TimeConfig {
 pub struct EnvironmentConfig {
     pub starting_hour: u8,
     pub sun_energy_output_per_tile: f32,
-    pub sun_energy_output_per_plant: f32,
+    /// light shared by all plants on a tile, see `PlantConfig::light_extinction_coefficient`
+    pub sun_light_per_tile: f32,
     pub sun_day_energy_ratio: f32,
     pub sun_night_energy_ratio: f32,
 
@@ -338,11 +608,35 @@ pub struct EnvironmentConfig {
     pub humidity_spread_coefficient: f32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DiseaseConfig {
+    /// chance of each organism from the starting population to be infected, `0.0` means there is no disease
+    pub initial_infection_chance: f32,
+    /// chance per time unit of passing the infection to each organism on the same tile
+    pub contact_transmission_chance: f32,
+    /// chance of passing the infection between attacker and its victim with each attack
+    pub predation_transmission_chance: f32,
+    /// chance of getting infected with each bite of infected carcass
+    pub carcass_transmission_chance: f32,
+    /// time units before infected organism becomes sick and infectious
+    pub incubation_time: u32,
+    /// time units of being sick, after which organism either recovers or dies
+    pub infection_duration: u32,
+    /// energy taken from sick organism every time unit
+    pub energy_drain: f32,
+    /// chance that the infection ends with death instead of recovery
+    pub mortality: f32,
+    /// lowers chances of getting infected and of dying from the infection
+    pub resistance_gene_config: FloatGeneConfig,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DataCollectionConfig {
     pub directory: String,
     pub plants_filename: String,
     pub animals_filename: String,
+    pub speciation_filename: String,
+    pub lineage_filename: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -364,11 +658,35 @@ thread_local! {
 pub struct FloatGeneConfig {
     pub multiplier: f32,
     pub offset: f32,
+    /// expression level of the gene in organisms spawned at the start of the simulation
+    #[serde(default = "default_starting_expression")]
+    pub starting_expression: f32,
+    /// chance that an allele mutates when it's passed to offspring (or when a founder is spawned)
+    #[serde(default = "default_mutation_rate")]
+    pub mutation_rate: f32,
+}
+
+fn default_starting_expression() -> f32 {
+    0.5
+}
+
+fn default_mutation_rate() -> f32 {
+    0.05
 }
 
 impl FloatGeneConfig {
     pub fn new(multiplier: f32, offset: f32) -> Self {
-        Self { multiplier, offset }
+        Self {
+            multiplier,
+            offset,
+            starting_expression: default_starting_expression(),
+            mutation_rate: default_mutation_rate(),
+        }
+    }
+
+    pub fn with_starting_expression(mut self, starting_expression: f32) -> Self {
+        self.starting_expression = starting_expression;
+        self
     }
 }
 
@@ -377,6 +695,9 @@ impl FloatGeneConfig {
 pub struct IntGeneConfig {
     pub max_value: u32,
     pub min_value: u32,
+    /// chance that an allele mutates when it's passed to offspring (or when a founder is spawned)
+    #[serde(default = "default_mutation_rate")]
+    pub mutation_rate: f32,
 }
 
 impl IntGeneConfig {
@@ -384,6 +705,7 @@ impl IntGeneConfig {
         Self {
             max_value,
             min_value,
+            mutation_rate: default_mutation_rate(),
         }
     }
 }
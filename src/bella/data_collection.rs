@@ -1,8 +1,11 @@
 use super::{
     config::SimulationConfig, organism::{
         animal::{AnimalMarker, Diet},
+        disease::Infection,
+        lineage::Lineage,
         plant::{PlantEnergyEfficiency, PlantMarker},
-        Energy, EnergyData, Health, OrganismEnergyEfficiency,
+        species::{SpeciationEvent, SpeciesId},
+        Energy, EnergyData, Health, OrganismEnergyEfficiency, Sex,
     }, restart::SimulationState, time::{SimulationTime, TimeUnitPassedEvent}
 };
 use bevy::prelude::*;
@@ -20,7 +23,11 @@ impl Plugin for DataCollectionPlugin {
         app.add_systems(OnEnter(SimulationState::InitializeDataCollection), (initialize_data_collection_directory,))
             .add_systems(
                 PostUpdate,
-                (save_plant_data, save_animal_data).run_if(on_event::<TimeUnitPassedEvent>),
+                (save_plant_data, save_animal_data, save_lineage_data).run_if(on_event::<TimeUnitPassedEvent>),
+            )
+            .add_systems(
+                PostUpdate,
+                save_speciation_data.run_if(on_event::<SpeciationEvent>),
             );
     }
 }
@@ -47,6 +54,11 @@ pub struct Plant {
     pub id: u64,
     pub time_unit: u64,
     pub day: u64,
+    pub species: u32,
+    /// `None` for plants without separate sexes
+    pub sex: Option<String>,
+    /// `None` for healthy plants
+    pub infection: Option<String>,
     // pub health: f32,
 
     // pub active_energy: Energy,
@@ -62,7 +74,10 @@ pub struct Plant {
 pub fn save_plant_data(
     plants: Query<
         (
-            Entity,
+            &Lineage,
+            &SpeciesId,
+            Option<&Sex>,
+            Option<&Infection>,
             // &Health,
             // &EnergyData,
             // &OrganismEnergyEfficiency,
@@ -78,15 +93,21 @@ pub fn save_plant_data(
         .iter()
         .map(
             |(
-                entity,
+                lineage,
+                species_id,
+                sex,
+                infection,
                 // health,
                 // energy_data,
                 // organism_energy_efficiency,
                 // plant_organism_efficiency,
             )| Plant {
-                id: entity.to_bits(),
+                id: lineage.id,
                 time_unit: time.time_units_this_day(),
                 day: time.days_passed(),
+                species: species_id.0,
+                sex: sex.map(|sex| sex.label().to_string()),
+                infection: infection.map(|infection| infection.stage.label().to_string()),
                 // health: health.hp,
 
                 // active_energy: energy_data.active_energy,
@@ -114,8 +135,17 @@ pub struct Animal {
     pub id: u64,
     pub time_unit: u64,
     pub day: u64,
+    pub species: u32,
 
     pub diet: String,
+    pub sex: String,
+    /// total damage taken during the whole life, to tell deaths by starvation, predation, disease and poisoning apart
+    pub starvation_damage: f32,
+    pub predation_damage: f32,
+    pub disease_damage: f32,
+    pub poisoning_damage: f32,
+    /// `None` for healthy animals
+    pub infection: Option<String>,
     // pub health: f32,
     // pub size: f32,
 
@@ -127,7 +157,18 @@ pub struct Animal {
 }
 
 pub fn save_animal_data(
-    animals: Query<(Entity, &Health, &EnergyData, &Diet), With<AnimalMarker>>,
+    animals: Query<
+        (
+            &Lineage,
+            &Health,
+            &EnergyData,
+            &Diet,
+            &SpeciesId,
+            &Sex,
+            Option<&Infection>,
+        ),
+        With<AnimalMarker>,
+    >,
     directory_path: Res<DirectoryPath>,
     time: Res<SimulationTime>,
     config: Res<SimulationConfig>,
@@ -135,9 +176,10 @@ pub fn save_animal_data(
     let animals: Vec<_> = animals
         .iter()
         .map(|x| Animal {
-            id: x.0.to_bits(),
+            id: x.0.id,
             time_unit: time.time_units_this_day(),
             day: time.days_passed(),
+            species: x.4 .0,
 
             diet: match x.3 {
                 Diet::Carnivore => "c",
@@ -145,6 +187,12 @@ pub fn save_animal_data(
                 Diet::Omnivore => "o",
             }
             .to_string(),
+            sex: x.5.label().to_string(),
+            starvation_damage: x.1.starvation_damage,
+            predation_damage: x.1.predation_damage,
+            disease_damage: x.1.disease_damage,
+            poisoning_damage: x.1.poisoning_damage,
+            infection: x.6.map(|infection| infection.stage.label().to_string()),
             // health: x.1.hp,
             // size: x.2.size,
             // energy: x.3.energy,
@@ -162,6 +210,78 @@ pub fn save_animal_data(
     );
 }
 
+/// Entry of the speciation log, written when a group of organisms splits off into a new species
+#[derive(Debug, Serialize)]
+pub struct Speciation {
+    pub day: u64,
+    pub species: u32,
+    pub parent_species: u32,
+    pub population: usize,
+}
+
+pub fn save_speciation_data(
+    mut event_reader: EventReader<SpeciationEvent>,
+    directory_path: Res<DirectoryPath>,
+    time: Res<SimulationTime>,
+    config: Res<SimulationConfig>,
+) {
+    let speciations: Vec<_> = event_reader
+        .read()
+        .map(|event| Speciation {
+            day: time.days_passed(),
+            species: event.species.0,
+            parent_species: event.parent.0,
+            population: event.population,
+        })
+        .collect();
+
+    save_data(
+        &speciations,
+        &directory_path,
+        &config.data_collection.speciation_filename,
+    );
+}
+
+/// Row of the lineage table, written once when an organism is born (or spawned at the start), so it outlives the organism
+#[derive(Debug, Serialize)]
+pub struct LineageRecord {
+    pub id: u64,
+    pub mother: Option<u64>,
+    pub father: Option<u64>,
+    pub generation: u32,
+    pub born_on_day: u64,
+    pub born_at_time_unit: u64,
+    pub is_animal: bool,
+}
+
+pub fn save_lineage_data(
+    organisms: Query<
+        (&Lineage, Has<AnimalMarker>),
+        (Added<Lineage>, Or<(With<AnimalMarker>, With<PlantMarker>)>),
+    >,
+    directory_path: Res<DirectoryPath>,
+    config: Res<SimulationConfig>,
+) {
+    let records: Vec<_> = organisms
+        .iter()
+        .map(|(lineage, is_animal)| LineageRecord {
+            id: lineage.id,
+            mother: lineage.mother,
+            father: lineage.father,
+            generation: lineage.generation,
+            born_on_day: lineage.born_on_day,
+            born_at_time_unit: lineage.born_at_time_unit,
+            is_animal,
+        })
+        .collect();
+
+    save_data(
+        &records,
+        &directory_path,
+        &config.data_collection.lineage_filename,
+    );
+}
+
 const BUFFER_CAPACITY: usize = 1024 * 1024;
 
 fn save_data<T: Serialize>(elements: &[T], directory_path: &DirectoryPath, filename: &str) {
@@ -17,7 +17,7 @@ impl Plugin for EnvironmentPlugin {
 pub struct Sun {
     day_time: u8,
     energy_output_per_tile: f32,
-    energy_output_per_plant: f32,
+    light_per_tile: f32,
     day_energy_ratio: f32,
     night_energy_ratio: f32,
 }
@@ -31,8 +31,9 @@ impl Sun {
         self.energy_output_per_tile * self.get_energy_ratio()
     }
 
-    pub fn get_energy_for_plant(&self) -> f32 {
-        self.energy_output_per_plant
+    /// Light budget of a tile, plants on it compete for it
+    pub fn get_light_for_tile(&self) -> f32 {
+        self.light_per_tile
     }
 
     fn get_energy_ratio(&self) -> f32 {
@@ -48,7 +49,7 @@ fn create_sun(mut cmd: Commands, config: Res<SimulationConfig>) {
     cmd.insert_resource(Sun {
         day_time: config.environment.starting_hour,
         energy_output_per_tile: config.environment.sun_energy_output_per_tile,
-        energy_output_per_plant: config.environment.sun_energy_output_per_plant,
+        light_per_tile: config.environment.sun_light_per_tile,
         day_energy_ratio: config.environment.sun_day_energy_ratio,
         night_energy_ratio: config.environment.sun_night_energy_ratio,
     });
@@ -3,10 +3,17 @@ use super::{
         animal::AnimalMarker,
         gene::{Allele, FloatGene, Gene, IntGene},
         plant::PlantMarker,
+        species::SpeciesRegistry,
+    },
+    terrain::{
+        overlays::OverlayLegend,
+        terrain_overlay_state::{AnimalDensityFilter, TerrainOverlayState},
+        thermal_conductor::ThermalConductor,
+        tile::{Tile, TileLayout},
+        BiomeType, Humidity, Nutrients, ObjectKind, ObjectsInTile, TerrainMarker,
     },
-    terrain::{tile::TileLayout, TerrainMarker},
     time::{DayTimer, SimulationTime, TimeUnitTimer},
-    ui_facade::{ChosenEntity, EguiFocusState, EguiVisibleState},
+    ui_facade::{ChosenEntity, ChosenTile, EguiFocusState, EguiVisibleState},
 };
 use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_egui::egui::{text::LayoutJob, Color32, TextFormat};
@@ -38,7 +45,13 @@ impl Plugin for InspectorPlugin {
             )
             .add_systems(
                 Update,
-                (chosen_entity_ui, resources_ui, entities_ui)
+                (
+                    chosen_entity_ui,
+                    chosen_tile_ui,
+                    resources_ui,
+                    entities_ui,
+                    overlays_ui,
+                )
                     .run_if(in_state(EguiVisibleState::Yes)),
             )
             .add_systems(
@@ -91,6 +104,112 @@ fn chosen_entity_ui(world: &mut World) {
     }
 }
 
+fn chosen_tile_ui(world: &mut World) {
+    let Some(tile_entity) = world.resource::<ChosenTile>().entity else {
+        return;
+    };
+
+    // NOTE: tile disappears when terrain is generated again after restart
+    let Ok(entity_ref) = world.get_entity(tile_entity) else {
+        world.resource_mut::<ChosenTile>().entity = None;
+        return;
+    };
+
+    let (
+        Some(tile),
+        Some(biome),
+        Some(humidity),
+        Some(nutrients),
+        Some(thermal_conductor),
+        Some(objects_in_tile),
+    ) = (
+        entity_ref.get::<Tile>(),
+        entity_ref.get::<BiomeType>(),
+        entity_ref.get::<Humidity>(),
+        entity_ref.get::<Nutrients>(),
+        entity_ref.get::<ThermalConductor>(),
+        entity_ref.get::<ObjectsInTile>(),
+    )
+    else {
+        world.resource_mut::<ChosenTile>().entity = None;
+        return;
+    };
+
+    let properties = [
+        (
+            "Coordinates",
+            format!("col: {}, row: {}", tile.col, tile.row),
+        ),
+        ("Biome", format!("{:?}", biome)),
+        ("Humidity", format!("{:.2}", humidity.value)),
+        (
+            "Nutrients",
+            format!(
+                "{:.2} (base: {:.2})",
+                nutrients.value(),
+                nutrients.base_value()
+            ),
+        ),
+        (
+            "Temperature",
+            format!("{:.2}", thermal_conductor.temperature()),
+        ),
+    ];
+
+    let objects: Vec<(&str, Vec<Entity>)> = [
+        ("Plants", ObjectKind::Plant),
+        ("Animals", ObjectKind::Animal),
+        ("Plant carcasses", ObjectKind::PlantCarcass),
+        ("Animal carcasses", ObjectKind::AnimalCarcass),
+        ("Seeds", ObjectKind::Seed),
+    ]
+    .into_iter()
+    .map(|(label, kind)| (label, objects_in_tile.get(kind).iter().copied().collect()))
+    .collect();
+
+    let mut egui_context = match world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    {
+        Ok(egui_context) => egui_context.clone(),
+        Err(_) => return,
+    };
+
+    egui::Window::new("Tile")
+        .default_open(true)
+        .default_pos((0.0, 140.0))
+        .show(egui_context.get_mut(), |ui| {
+            if ui.button("Clear chosen tile").clicked() {
+                world.resource_mut::<ChosenTile>().entity = None;
+            }
+
+            ui.separator();
+
+            egui::Grid::new("tile_properties").show(ui, |ui| {
+                for (label, value) in properties.iter() {
+                    ui.label(*label);
+                    ui.label(value.as_str());
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (label, entities) in objects.iter() {
+                    ui.collapsing(format!("{} ({})", label, entities.len()), |ui| {
+                        for entity in entities.iter() {
+                            // NOTE: clicking the link shows the entity in the "Chosen entity" window
+                            if ui.link(format!("{:?}", entity)).clicked() {
+                                world.resource_mut::<ChosenEntity>().entity = Some(*entity);
+                            }
+                        }
+                    });
+                }
+            });
+        });
+}
+
 fn resources_ui(world: &mut World) {
     let mut egui_context = match world
         .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
@@ -165,6 +284,76 @@ fn entities_ui(world: &mut World) {
         });
 }
 
+fn overlays_ui(world: &mut World) {
+    let mut egui_context = match world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    {
+        Ok(egui_context) => egui_context.clone(),
+        Err(_) => return,
+    };
+
+    egui::Window::new("Overlays")
+        .default_open(true)
+        .anchor(egui::Align2::RIGHT_TOP, (-5.0, 60.0))
+        .show(egui_context.get_mut(), |ui| {
+            let current_state = **world.resource::<State<TerrainOverlayState>>();
+            let mut chosen_state = current_state;
+
+            for overlay in TerrainOverlayState::ALL {
+                ui.radio_value(&mut chosen_state, overlay, overlay.label());
+            }
+
+            if chosen_state != current_state {
+                world
+                    .resource_mut::<NextState<TerrainOverlayState>>()
+                    .set(chosen_state);
+            }
+
+            if current_state == TerrainOverlayState::AnimalDensity {
+                ui.separator();
+
+                // NOTE: resource is changed only on a new choice, every change repaints the overlay
+                let current_species = world.resource::<AnimalDensityFilter>().species;
+                let mut chosen_species = current_species;
+                let options = AnimalDensityFilter::options(world.resource::<SpeciesRegistry>());
+
+                egui::ComboBox::from_label("Species")
+                    .selected_text(AnimalDensityFilter::option_label(&current_species))
+                    .show_ui(ui, |ui| {
+                        for species_id in options {
+                            let label = AnimalDensityFilter::option_label(&species_id);
+                            ui.selectable_value(&mut chosen_species, species_id, label);
+                        }
+                    });
+
+                if chosen_species != current_species {
+                    world.resource_mut::<AnimalDensityFilter>().species = chosen_species;
+                }
+            }
+
+            ui.separator();
+
+            let legend = world.resource::<OverlayLegend>();
+            ui.label(legend.title.as_str());
+
+            for (label, color) in legend.entries.iter() {
+                let [r, g, b, a] = color.to_srgba().to_u8_array();
+
+                ui.horizontal(|ui| {
+                    let (rect, _) =
+                        ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                    ui.painter().rect_filled(
+                        rect,
+                        0.0,
+                        Color32::from_rgba_unmultiplied(r, g, b, a),
+                    );
+                    ui.label(label.as_str());
+                });
+            }
+        });
+}
+
 fn update_egui_visible_state_based_on_keyboard_input(
     current_state: Res<State<EguiVisibleState>>,
     mut next_state: ResMut<NextState<EguiVisibleState>>,
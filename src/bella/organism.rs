@@ -1,39 +1,65 @@
 pub mod animal;
 pub mod carcass;
+pub mod disease;
 pub mod gene;
+pub mod lineage;
 pub mod plant;
+pub mod species;
 
 use std::time::Duration;
 
 use self::plant::PlantPlugin;
-use super::time::TimeUnitPassedEvent;
-use animal::AnimalPlugin;
+use super::{
+    config::{OrganismConfig, SimulationConfig},
+    terrain::{thermal_conductor::TileTemperature, tile::TileLayout},
+    time::TimeUnitPassedEvent,
+};
+use animal::{conceive, AnimalPlugin, Pregnancy};
 use bevy::prelude::*;
 use carcass::CarcassPlugin;
+use disease::{DiseasePlugin, DiseaseResistance};
 use gene::{FloatGene, GenePlugin, IntGene};
+use lineage::LineagePlugin;
+use species::SpeciesPlugin;
 
 pub struct OrganismPlugin;
 
 impl Plugin for OrganismPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((GenePlugin, PlantPlugin, AnimalPlugin, CarcassPlugin))
-            .register_type::<Health>()
-            .register_type::<Age>()
-            .register_type::<SexualMaturity>()
-            .register_type::<EnergyData>()
-            .register_type::<OrganismEnergyEfficiency>()
-            .add_event::<KillOrganismEvent>()
-            .add_systems(
-                Update,
-                (
-                    increase_age,
-                    increase_sexual_maturity_level_for_youngs,
-                    decrease_reproduction_cooldown_timer,
-                    consume_energy_to_survive,
-                    adjust_size,
-                )
-                    .run_if(on_event::<TimeUnitPassedEvent>),
-            );
+        app.add_plugins((
+            GenePlugin,
+            PlantPlugin,
+            AnimalPlugin,
+            CarcassPlugin,
+            SpeciesPlugin,
+            LineagePlugin,
+            DiseasePlugin,
+        ))
+        .register_type::<Health>()
+        .register_type::<DamageSource>()
+        .register_type::<Age>()
+        .register_type::<Senescence>()
+        .register_type::<SexualMaturity>()
+        .register_type::<EnergyData>()
+        .register_type::<OrganismEnergyEfficiency>()
+        .register_type::<ThermalTolerance>()
+        .register_type::<Sex>()
+        .add_event::<KillOrganismEvent>()
+        .add_systems(
+            Update,
+            (
+                increase_age,
+                die_of_old_age,
+                increase_sexual_maturity_level_for_youngs,
+                // NOTE: mother who has just mated for the last time must be pregnant already, Bevy applies commands of `conceive` in between
+                end_reproductive_life.after(conceive),
+                decrease_reproduction_cooldown_timer,
+                consume_energy_to_survive,
+                regenerate_health,
+                adjust_size,
+            )
+                .run_if(on_event::<TimeUnitPassedEvent>),
+        );
     }
 }
 
@@ -50,24 +76,103 @@ pub struct BasicBundle {
 pub struct OrganismBundle {
     health: Health,
     age: Age,
+    senescence: Senescence,
     sexual_maturity: SexualMaturity,
     energy_data: EnergyData,
     organism_energy_efficiency: OrganismEnergyEfficiency,
+    thermal_tolerance: ThermalTolerance,
+    disease_resistance: DiseaseResistance,
 }
 
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct Health {
     pub hp: f32,
     max_hp_gene: FloatGene,
+    /// hp regenerated per time unit, paid for with spare active energy
+    pub regeneration_gene: FloatGene,
+    /// missing hp caused by attacks, slows organism down and weakens its attacks until healed
+    pub injury: f32,
+    /// total damage taken from each source during the whole life
+    pub starvation_damage: f32,
+    pub predation_damage: f32,
+    pub disease_damage: f32,
+    pub poisoning_damage: f32,
+}
+
+/// What damaged the organism
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageSource {
+    /// not enough energy to survive
+    Starvation,
+    /// attacked by an animal
+    Predation,
+    /// reached the end of its lifespan
+    OldAge,
+    /// reproduced for the last time and there is no post-reproductive stage
+    ReproductiveDeath,
+    /// drained by an infection or killed by it
+    Disease,
+    /// grazed a toxic plant
+    Poisoning,
+    /// its tile was changed into a biome it can't live in
+    Habitat,
 }
 
 impl Health {
-    pub fn new(gene: FloatGene) -> Self {
+    pub fn new(max_hp_gene: FloatGene, regeneration_gene: FloatGene) -> Self {
         Self {
-            hp: gene.phenotype() / 2.0,
-            max_hp_gene: gene,
+            hp: max_hp_gene.phenotype() / 2.0,
+            max_hp_gene,
+            regeneration_gene,
+            injury: 0.0,
+            starvation_damage: 0.0,
+            predation_damage: 0.0,
+            disease_damage: 0.0,
+            poisoning_damage: 0.0,
         }
     }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(
+            self.max_hp_gene.mixed_with(&other.max_hp_gene),
+            self.regeneration_gene.mixed_with(&other.regeneration_gene),
+        )
+    }
+
+    pub fn get_max_hp(&self) -> f32 {
+        self.max_hp_gene.phenotype()
+    }
+
+    pub fn take_damage(&mut self, damage: f32, source: DamageSource) {
+        self.hp -= damage;
+
+        match source {
+            DamageSource::Starvation => self.starvation_damage += damage,
+            DamageSource::Predation => {
+                self.predation_damage += damage;
+                self.injury += damage;
+            }
+            DamageSource::Disease => self.disease_damage += damage,
+            DamageSource::Poisoning => self.poisoning_damage += damage,
+            DamageSource::OldAge | DamageSource::ReproductiveDeath | DamageSource::Habitat => (),
+        }
+    }
+
+    /// Heal up to `amount`, never above max hp. Injuries heal along with hp. Returns hp actually healed.
+    pub fn heal(&mut self, amount: f32) -> f32 {
+        let healed = amount.min(self.get_max_hp() - self.hp).max(0.0);
+        self.hp += healed;
+        self.injury = self.injury.min(self.get_max_hp() - self.hp).max(0.0);
+
+        healed
+    }
+
+    /// Multiplier for speed and attack damage, `1.0` when uninjured, `1.0 - max_penalty` when injured with all hp missing
+    pub fn get_injury_multiplier(&self, max_penalty: f32) -> f32 {
+        let injury_ratio = (self.injury / self.get_max_hp().max(f32::EPSILON)).clamp(0.0, 1.0);
+
+        1.0 - max_penalty * injury_ratio
+    }
 }
 
 #[derive(Component, Reflect, Debug, Clone)]
@@ -103,6 +208,25 @@ impl EnergyData {
         }
     }
 
+    /// Energy data of a newborn, `mass_ratio` of the energy invested by its mother becomes body mass and the rest active energy
+    pub fn from_invested_energy(
+        max_active_energy_gene: FloatGene,
+        energy_per_mass_unit_gene: FloatGene,
+        invested_energy: Energy,
+        mass_ratio: f32,
+    ) -> Self {
+        let mass = invested_energy * mass_ratio / energy_per_mass_unit_gene.phenotype();
+        let active_energy =
+            (invested_energy * (1.0 - mass_ratio)).min(max_active_energy_gene.phenotype());
+
+        Self {
+            active_energy,
+            max_active_energy_gene,
+            mass,
+            energy_per_mass_unit_gene,
+        }
+    }
+
     pub fn get_mass_equivalent_of_energy(&self, energy: Energy) -> f32 {
         energy / self.energy_per_mass_unit_gene.phenotype()
     }
@@ -208,10 +332,92 @@ impl OrganismEnergyEfficiency {
     }
 }
 
+/// How well organism handles temperature of the tile it's on
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct ThermalTolerance {
+    /// temperature in which organism doesn't suffer any thermal stress
+    pub optimal_temperature_gene: FloatGene,
+    /// distance from optimal temperature at which thermal stress equals `1.0`
+    pub tolerance_width_gene: FloatGene,
+}
+
+impl ThermalTolerance {
+    pub fn new(optimal_temperature_gene: FloatGene, tolerance_width_gene: FloatGene) -> Self {
+        Self {
+            optimal_temperature_gene,
+            tolerance_width_gene,
+        }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(
+            self.optimal_temperature_gene
+                .mixed_with(&other.optimal_temperature_gene),
+            self.tolerance_width_gene
+                .mixed_with(&other.tolerance_width_gene),
+        )
+    }
+
+    /// `0.0` in optimal temperature, grows quadratically with distance from it
+    pub fn get_thermal_stress(&self, temperature: f32, min_tolerance_width: f32) -> f32 {
+        let distance = (temperature - self.optimal_temperature_gene.phenotype()).abs();
+        let width = self
+            .tolerance_width_gene
+            .phenotype()
+            .max(min_tolerance_width);
+
+        (distance / width).powi(2)
+    }
+
+    /// Multiplier for energy needed to survive, `>= 1.0`
+    pub fn get_metabolic_cost_multiplier(&self, temperature: f32, min_tolerance_width: f32) -> f32 {
+        1.0 + self.get_thermal_stress(temperature, min_tolerance_width)
+    }
+
+    /// Multiplier for energy produced by plants, in range `(0.0, 1.0]`
+    pub fn get_production_multiplier(&self, temperature: f32, min_tolerance_width: f32) -> f32 {
+        1.0 / (1.0 + self.get_thermal_stress(temperature, min_tolerance_width))
+    }
+}
+
+/// Mating is only possible between organisms of opposite sexes, only females pay for reproduction and give birth
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sex {
+    Female,
+    Male,
+}
+
+impl Sex {
+    /// Mother always passes X chromosome and father passes X or Y with equal chance, so both sexes are equally likely
+    pub fn random() -> Self {
+        if rand::random::<bool>() {
+            Sex::Female
+        } else {
+            Sex::Male
+        }
+    }
+
+    /// Organisms without sex (e.g. hermaphroditic plants) can only mate with each other
+    pub fn can_mate(sex1: Option<&Sex>, sex2: Option<&Sex>) -> bool {
+        match (sex1, sex2) {
+            (Some(sex1), Some(sex2)) => sex1 != sex2,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Sex::Female => "f",
+            Sex::Male => "m",
+        }
+    }
+}
+
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct Age {
     pub value: u32,
-    // TODO: to raczej nie powinno być takie ogólne, tylko osobne dla każdego komponentu, który osłabiany jest z wiekiem
+    /// only raises metabolic cost, other traits weaken with age according to `Senescence`
     pub age_penalty_gene: FloatGene,
 }
 
@@ -228,19 +434,98 @@ impl Age {
     }
 }
 
+/// Trait doesn't weaken until `onset_gene` age, then loses `rate_gene` of its value every time unit
+#[derive(Reflect, Debug, Clone)]
+pub struct SenescenceCurve {
+    /// in time units
+    pub onset_gene: IntGene,
+    pub rate_gene: FloatGene,
+}
+
+impl SenescenceCurve {
+    pub fn new(onset_gene: IntGene, rate_gene: FloatGene) -> Self {
+        Self {
+            onset_gene,
+            rate_gene,
+        }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(
+            self.onset_gene.mixed_with(&other.onset_gene),
+            self.rate_gene.mixed_with(&other.rate_gene),
+        )
+    }
+
+    /// Multiplier for the trait, `1.0` before onset, falls linearly to `0.0` after it
+    pub fn get_multiplier(&self, age: &Age) -> f32 {
+        let time_since_onset = age.value.saturating_sub(self.onset_gene.phenotype()) as f32;
+
+        (1.0 - self.rate_gene.phenotype() * time_since_onset).max(0.0)
+    }
+}
+
+/// How long organism can live and how each of its traits weakens with age.
+/// Plants don't use `speed`, `sight` and `attack`, animals don't use `photosynthesis`, but they are still inherited.
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct Senescence {
+    /// organism dies of old age when it reaches this age, in time units
+    pub lifespan_gene: IntGene,
+    pub speed: SenescenceCurve,
+    pub sight: SenescenceCurve,
+    pub attack: SenescenceCurve,
+    /// chance of conceiving each offspring
+    pub fertility: SenescenceCurve,
+    pub photosynthesis: SenescenceCurve,
+}
+
+impl Senescence {
+    /// Senescence of organisms spawned at the start of the simulation, every trait has its own genes with the same config
+    pub fn from_config(config: &OrganismConfig) -> Self {
+        let curve = || {
+            SenescenceCurve::new(
+                config.senescence_onset_gene_config.into(),
+                config.senescence_rate_gene_config.into(),
+            )
+        };
+
+        Self {
+            lifespan_gene: config.lifespan_gene_config.into(),
+            speed: curve(),
+            sight: curve(),
+            attack: curve(),
+            fertility: curve(),
+            photosynthesis: curve(),
+        }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self {
+            lifespan_gene: self.lifespan_gene.mixed_with(&other.lifespan_gene),
+            speed: self.speed.mixed_with(&other.speed),
+            sight: self.sight.mixed_with(&other.sight),
+            attack: self.attack.mixed_with(&other.attack),
+            fertility: self.fertility.mixed_with(&other.fertility),
+            photosynthesis: self.photosynthesis.mixed_with(&other.photosynthesis),
+        }
+    }
+}
+
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct SexualMaturity {
     pub level: SexualMaturityLevel,
     pub maturity_age_gene: IntGene,
     pub reproduction_cooldown_gene: IntGene,
-    // reproductions_left: u32,
-    // max_reproduction_count_gene: FloatGene,
+    pub reproductions_left: u32,
+    /// `1` means the organism reproduces only once (semelparity)
+    pub max_reproduction_count_gene: IntGene,
 }
 
 impl SexualMaturity {
     pub fn new(
         maturity_age_gene: IntGene,
         reproduction_cooldown_gene: IntGene,
+        max_reproduction_count_gene: IntGene,
         starting_age: u32,
     ) -> Self {
         let maturity_age = maturity_age_gene.phenotype();
@@ -269,17 +554,37 @@ impl SexualMaturity {
             level,
             maturity_age_gene,
             reproduction_cooldown_gene,
+            // NOTE: every organism gets at least one chance to reproduce
+            reproductions_left: max_reproduction_count_gene.phenotype().max(1),
+            max_reproduction_count_gene,
         }
     }
 
-    pub fn reset_reproduction_cooldown(&mut self) {
-        if let SexualMaturityLevel::Adult {
+    pub fn mixed_with(&self, other: &Self, starting_age: u32) -> Self {
+        Self::new(
+            self.maturity_age_gene.mixed_with(&other.maturity_age_gene),
+            self.reproduction_cooldown_gene
+                .mixed_with(&other.reproduction_cooldown_gene),
+            self.max_reproduction_count_gene
+                .mixed_with(&other.max_reproduction_count_gene),
+            starting_age,
+        )
+    }
+
+    /// Resets reproduction cooldown, or ends reproductive life if it was the last reproduction
+    pub fn register_reproduction(&mut self) {
+        let SexualMaturityLevel::Adult {
             reproduction_cooldown_timer,
         } = &mut self.level
-        {
+        else {
+            panic!("Trying to register reproduction for not Adult");
+        };
+
+        self.reproductions_left = self.reproductions_left.saturating_sub(1);
+        if self.reproductions_left > 0 {
             reproduction_cooldown_timer.reset();
         } else {
-            panic!("Trying to reset reproduction cooldown for not Adult");
+            self.level = SexualMaturityLevel::PostReproductive;
         }
     }
 
@@ -297,8 +602,14 @@ impl SexualMaturity {
 
 #[derive(Reflect, Debug, Clone)]
 pub enum SexualMaturityLevel {
-    Young { left_to_mature_timer: Timer },
-    Adult { reproduction_cooldown_timer: Timer },
+    Young {
+        left_to_mature_timer: Timer,
+    },
+    Adult {
+        reproduction_cooldown_timer: Timer,
+    },
+    /// Can't reproduce anymore, only reached if `post_reproductive_stage` is enabled in organism config, otherwise organism dies
+    PostReproductive,
 }
 
 #[derive(Event)]
@@ -312,6 +623,15 @@ fn increase_age(mut query: Query<&mut Age>) {
     }
 }
 
+fn die_of_old_age(mut query: Query<(&Age, &Senescence, &mut Health)>) {
+    for (age, senescence, mut health) in query.iter_mut() {
+        if age.value >= senescence.lifespan_gene.phenotype() {
+            let hp = health.hp.max(0.0);
+            health.take_damage(hp, DamageSource::OldAge);
+        }
+    }
+}
+
 fn increase_sexual_maturity_level_for_youngs(mut query: Query<&mut SexualMaturity>) {
     for mut sexual_maturity in query.iter_mut() {
         if let SexualMaturityLevel::Young {
@@ -333,6 +653,23 @@ fn increase_sexual_maturity_level_for_youngs(mut query: Query<&mut SexualMaturit
     }
 }
 
+/// Without post-reproductive stage, organisms die after their last reproduction, mothers only once they give birth
+fn end_reproductive_life(
+    mut query: Query<(&SexualMaturity, &mut Health, Has<Pregnancy>)>,
+    config: Res<SimulationConfig>,
+) {
+    if config.organism.post_reproductive_stage {
+        return;
+    }
+
+    for (sexual_maturity, mut health, is_pregnant) in query.iter_mut() {
+        if matches!(sexual_maturity.level, SexualMaturityLevel::PostReproductive) && !is_pregnant {
+            let hp = health.hp.max(0.0);
+            health.take_damage(hp, DamageSource::ReproductiveDeath);
+        }
+    }
+}
+
 fn decrease_reproduction_cooldown_timer(mut query: Query<&mut SexualMaturity>) {
     for mut sexual_maturity in query.iter_mut() {
         if let SexualMaturityLevel::Adult {
@@ -348,21 +685,63 @@ fn consume_energy_to_survive(
     mut query: Query<(
         &mut EnergyData,
         &OrganismEnergyEfficiency,
+        &ThermalTolerance,
         &Age,
         &mut Health,
+        &Transform,
     )>,
+    tile_temperatures: Query<&TileTemperature>,
+    tile_layout: Res<TileLayout>,
+    config: Res<SimulationConfig>,
 ) {
-    for (mut energy_data, energy_efficiency, age, mut health) in query.iter_mut() {
+    for (mut energy_data, energy_efficiency, thermal_tolerance, age, mut health, transform) in
+        query.iter_mut()
+    {
+        let thermal_cost_multiplier = tile_layout
+            .try_get_tile_entity_for_transform(transform)
+            .and_then(|tile_entity| tile_temperatures.get(tile_entity).ok())
+            .map_or(1.0, |tile_temperature| {
+                thermal_tolerance.get_metabolic_cost_multiplier(
+                    tile_temperature.value,
+                    config.organism.min_temperature_tolerance,
+                )
+            });
+
         let energy_to_survive = energy_data.mass
             * energy_efficiency
                 .energy_consumption_to_survive_per_mass_unit_gene
                 .phenotype()
-            * age.get_age_penalty();
+            * age.get_age_penalty()
+            * thermal_cost_multiplier;
 
         let energy_left_to_consume = energy_data.consume_from_active_energy(energy_to_survive);
         if energy_left_to_consume > 0.0 {
-            health.hp -= convert_energy_to_damage(energy_left_to_consume);
+            health.take_damage(
+                convert_energy_to_damage(energy_left_to_consume),
+                DamageSource::Starvation,
+            );
+        }
+    }
+}
+
+/// Organisms with spare energy (satiated) regenerate hp, paying `regeneration_energy_cost` per hp
+fn regenerate_health(
+    mut query: Query<(&mut Health, &mut EnergyData)>,
+    config: Res<SimulationConfig>,
+) {
+    let energy_cost_per_hp = config.organism.regeneration_energy_cost;
+
+    for (mut health, mut energy_data) in query.iter_mut() {
+        if health.hp >= health.get_max_hp()
+            || !matches!(energy_data.get_hunger_level(), HungerLevel::Satiated)
+        {
+            continue;
         }
+
+        let affordable_hp = energy_data.active_energy / energy_cost_per_hp.max(f32::EPSILON);
+        let regeneration = health.regeneration_gene.phenotype().min(affordable_hp);
+        let healed = health.heal(regeneration);
+        energy_data.active_energy -= healed * energy_cost_per_hp;
     }
 }
 
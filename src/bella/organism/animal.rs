@@ -1,13 +1,16 @@
 pub mod animal_ai;
 #[cfg(not(feature = "bella_headless"))]
 pub mod gizmos;
-
-use std::cell::RefCell;
+pub mod parental_care;
 
 use self::animal_ai::Mobile;
 use super::{
-    gene::FloatGene, Age, BasicBundle, EnergyData, OrganismBundle, OrganismEnergyEfficiency,
-    SexualMaturity,
+    disease::DiseaseResistance,
+    gene::{FloatGene, IntGene, SexLimitedFloatGene},
+    lineage::{Lineage, OffspringLineage},
+    species::SpeciesId,
+    Age, BasicBundle, EnergyData, OrganismBundle, OrganismEnergyEfficiency, Senescence, Sex,
+    SexualMaturity, ThermalTolerance,
 };
 use crate::bella::{
     config::SimulationConfig,
@@ -20,30 +23,40 @@ use crate::bella::{
     time::TimeUnitPassedEvent,
     ui_facade::choose_entity_observer,
 };
-use animal_ai::{Action, AnimalAiPlugin};
+use animal_ai::{handle_action, Action, AnimalAiPlugin};
 use bevy::prelude::*;
-use rand::{rngs::ThreadRng, thread_rng, Rng};
-
-thread_local! {
-    static RNG: RefCell<ThreadRng> = RefCell::new(thread_rng());
-}
+use parental_care::{CaredForBy, ParentalCare, ParentalCarePlugin};
 
 pub struct AnimalPlugin;
 
 impl Plugin for AnimalPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(AnimalAiPlugin)
+        app.add_plugins((AnimalAiPlugin, ParentalCarePlugin))
             .register_type::<Diet>()
+            .register_type::<Digestion>()
             .register_type::<ActionRange>()
             .register_type::<SightRange>()
             .register_type::<AttackDmg>()
+            .register_type::<BiteSize>()
+            .register_type::<ToxinTolerance>()
+            .register_type::<Gestation>()
+            .register_type::<Pregnancy>()
+            .register_type::<Embryo>()
             .add_event::<ReproduceAnimalsEvent>()
             .add_systems(OnEnter(SimulationState::LoadAssets), prepare_animal_assets)
             .add_systems(OnEnter(SimulationState::OrganismGeneration), spawn_animals)
             .add_systems(OnExit(SimulationState::Simulation), despawn_all_animals)
             .add_systems(
                 Update,
-                reproduce.run_if(in_state(SimulationState::Simulation)),
+                conceive
+                    .after(handle_action)
+                    .run_if(in_state(SimulationState::Simulation)),
+            )
+            .add_systems(
+                Update,
+                give_birth
+                    .run_if(on_event::<TimeUnitPassedEvent>)
+                    .run_if(in_state(SimulationState::Simulation)),
             );
     }
 }
@@ -63,8 +76,14 @@ pub struct AnimalBundle {
     action_range: ActionRange,
     mobile: Mobile,
     attack: AttackDmg,
+    bite_size: BiteSize,
+    toxin_tolerance: ToxinTolerance,
     sight_range: SightRange,
+    digestion: Digestion,
+    gestation: Gestation,
+    parental_care: ParentalCare,
     diet: Diet,
+    sex: Sex,
     action: Action,
 }
 
@@ -87,16 +106,123 @@ impl AnimalEnergyEfficiency {
     }
 }
 
-#[derive(Component, Reflect, Debug, Clone)]
+#[derive(Component, Reflect, Debug, Clone, PartialEq, Eq)]
 pub enum Diet {
     Herbivore,
     Carnivore,
     Omnivore,
 }
 
+/// Efficiency of getting energy from eaten plant and animal matter, `Diet` of the animal is derived from it
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct Digestion {
+    pub plant_digestion_gene: FloatGene,
+    pub meat_digestion_gene: FloatGene,
+}
+
+impl Digestion {
+    pub fn new(plant_digestion_gene: FloatGene, meat_digestion_gene: FloatGene) -> Self {
+        Self {
+            plant_digestion_gene,
+            meat_digestion_gene,
+        }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(
+            self.plant_digestion_gene
+                .mixed_with(&other.plant_digestion_gene),
+            self.meat_digestion_gene
+                .mixed_with(&other.meat_digestion_gene),
+        )
+    }
+
+    /// Part of the energy stored in eaten matter that animal actually gets
+    pub fn get_efficiency(&self, is_meat: bool) -> f32 {
+        if is_meat {
+            self.meat_digestion_gene.phenotype()
+        } else {
+            self.plant_digestion_gene.phenotype()
+        }
+    }
+
+    pub fn get_diet(&self, threshold: f32) -> Diet {
+        let plant_efficiency = self.plant_digestion_gene.phenotype();
+        let meat_efficiency = self.meat_digestion_gene.phenotype();
+
+        match (plant_efficiency >= threshold, meat_efficiency >= threshold) {
+            (true, true) => Diet::Omnivore,
+            (true, false) => Diet::Herbivore,
+            (false, true) => Diet::Carnivore,
+            // NOTE: animal that can't digest anything well eats what it digests better
+            (false, false) if plant_efficiency >= meat_efficiency => Diet::Herbivore,
+            (false, false) => Diet::Carnivore,
+        }
+    }
+}
+
+/// Genes deciding how long the mother is pregnant and how many offspring are born at once
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct Gestation {
+    /// in time units
+    pub gestation_period_gene: IntGene,
+    pub litter_size_gene: IntGene,
+}
+
+impl Gestation {
+    pub fn new(gestation_period_gene: IntGene, litter_size_gene: IntGene) -> Self {
+        Self {
+            gestation_period_gene,
+            litter_size_gene,
+        }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(
+            self.gestation_period_gene
+                .mixed_with(&other.gestation_period_gene),
+            self.litter_size_gene.mixed_with(&other.litter_size_gene),
+        )
+    }
+}
+
+/// Mother can't mate again until she gives birth, which happens when `time_units_left` reaches `0`
+#[derive(Component, Reflect, Debug)]
+pub struct Pregnancy {
+    pub embryos: Vec<Embryo>,
+    pub time_units_left: u32,
+}
+
+/// Offspring developing inside its mother. Genes are mixed at conception, so the father isn't needed at birth.
+#[derive(Reflect, Debug, Clone)]
+pub struct Embryo {
+    health: Health,
+    age: Age,
+    senescence: Senescence,
+    sexual_maturity: SexualMaturity,
+    max_active_energy_gene: FloatGene,
+    energy_per_mass_unit_gene: FloatGene,
+    organism_energy_efficiency: OrganismEnergyEfficiency,
+    thermal_tolerance: ThermalTolerance,
+    disease_resistance: DiseaseResistance,
+    speed_gene: FloatGene,
+    action_range: ActionRange,
+    sight_range: SightRange,
+    attack: AttackDmg,
+    bite_size: BiteSize,
+    toxin_tolerance: ToxinTolerance,
+    digestion: Digestion,
+    gestation: Gestation,
+    parental_care: ParentalCare,
+    sex: Sex,
+    species_id: SpeciesId,
+    /// Mother's lineage is read at birth, but father might not live that long
+    father_lineage: Lineage,
+}
+
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct SightRange {
-    gene: FloatGene,
+    pub gene: FloatGene,
 }
 
 #[derive(Component, Reflect, Debug, Clone)]
@@ -106,6 +232,18 @@ pub struct ActionRange {
 
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct AttackDmg {
+    pub gene: SexLimitedFloatGene,
+}
+
+/// Mass of food (live plant, seed or carcass) that animal can eat in one time unit
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct BiteSize {
+    pub gene: FloatGene,
+}
+
+/// Neutralizes the part of plant toxicity up to the gene phenotype
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct ToxinTolerance {
     pub gene: FloatGene,
 }
 
@@ -148,27 +286,26 @@ fn spawn_animals(
 
         let animal_count = config.animal.group_size_dist.sample();
 
-        let diet = match config.animal.diet_dist.sample() {
+        // NOTE: species only decides starting genes, the actual diet comes from digestion genes
+        let species = match config.animal.diet_dist.sample() {
             0 => Diet::Herbivore,
             1 => Diet::Carnivore,
             _ => Diet::Omnivore,
         };
+        let species_config = config.animal_species.get(&species);
 
         for _ in 0..animal_count {
-            let health = Health::new(config.organism.max_health_gene_config.into());
+            let health = Health::new(
+                config.organism.max_health_gene_config.into(),
+                config.organism.regeneration_gene_config.into(),
+            );
             let starting_age = config.organism.starting_age_dist.sample();
             let age = Age::new(starting_age, config.organism.age_penalty_gene_config.into());
+            let senescence = Senescence::from_config(&config.organism);
             let sexual_maturity = SexualMaturity::new(
-                config
-                    .animal_species
-                    .get(&diet)
-                    .maturity_age_gene_config
-                    .into(),
-                config
-                    .animal_species
-                    .get(&diet)
-                    .reproduction_cooldown_gene_config
-                    .into(),
+                species_config.maturity_age_gene_config.into(),
+                species_config.reproduction_cooldown_gene_config.into(),
+                config.organism.max_reproduction_count_gene_config.into(),
                 starting_age,
             );
             let energy_data = EnergyData::new(
@@ -177,41 +314,55 @@ fn spawn_animals(
                 config.organism.starting_mass_dist.sample(),
             );
             let organism_energy_efficiency = OrganismEnergyEfficiency::new(
-                config
-                    .animal_species
-                    .get(&diet)
+                species_config
                     .energy_to_survive_per_mass_unit_gene_config
                     .into(),
-                config.organism.reproduction_energy_cost_gene_config.into(),
+                species_config.reproduction_energy_cost_gene_config.into(),
             );
 
+            let thermal_tolerance = ThermalTolerance::new(
+                config.organism.optimal_temperature_gene_config.into(),
+                config.organism.temperature_tolerance_gene_config.into(),
+            );
+            let disease_resistance =
+                DiseaseResistance::new(config.disease.resistance_gene_config.into());
+
             let animal_energy_efficiency = AnimalEnergyEfficiency::new();
             let mobile = Mobile {
-                speed: config.animal_species.get(&diet).speed_gene_config.into(),
+                speed: species_config.speed_gene_config.into(),
                 destination: None,
                 next_step_destination: None,
             };
             let sight_range = SightRange {
-                gene: config
-                    .animal_species
-                    .get(&diet)
-                    .sight_range_gene_config
-                    .into(),
+                gene: species_config.sight_range_gene_config.into(),
             };
             let action_range = ActionRange {
-                gene: config
-                    .animal_species
-                    .get(&diet)
-                    .action_range_gene_config
-                    .into(),
+                gene: species_config.action_range_gene_config.into(),
             };
+            let sex = Sex::random();
             let attack = AttackDmg {
-                gene: config
-                    .animal_species
-                    .get(&diet)
-                    .attack_damage_gene_config
-                    .into(),
+                gene: SexLimitedFloatGene::new(
+                    species_config.attack_damage_gene_config.into(),
+                    species_config.male_attack_damage_gene_config.into(),
+                    sex,
+                ),
             };
+            let bite_size = BiteSize {
+                gene: species_config.bite_size_gene_config.into(),
+            };
+            let toxin_tolerance = ToxinTolerance {
+                gene: species_config.toxin_tolerance_gene_config.into(),
+            };
+            let digestion = Digestion::new(
+                species_config.plant_digestion_gene_config.into(),
+                species_config.meat_digestion_gene_config.into(),
+            );
+            let gestation = Gestation::new(
+                species_config.gestation_period_gene_config.into(),
+                species_config.litter_size_gene_config.into(),
+            );
+            let parental_care = ParentalCare::new(species_config.care_duration_gene_config.into());
+            let diet = digestion.get_diet(config.animal.diet_digestion_threshold);
             let action = Action::DoingNothing { for_hours: 0 };
             let size = energy_data.get_size();
             let position = tile_layout.get_random_position_in_tile(tile);
@@ -228,9 +379,12 @@ fn spawn_animals(
                         organism_bundle: OrganismBundle {
                             health,
                             age,
+                            senescence,
                             sexual_maturity,
                             energy_data,
                             organism_energy_efficiency,
+                            thermal_tolerance,
+                            disease_resistance,
                         },
                         marker: AnimalMarker,
                         matter_marker: AnimalMatterMarker,
@@ -238,8 +392,14 @@ fn spawn_animals(
                         action_range,
                         mobile,
                         attack,
+                        bite_size,
+                        toxin_tolerance,
                         sight_range,
-                        diet: diet.clone(),
+                        digestion,
+                        gestation,
+                        parental_care,
+                        diet,
+                        sex,
                         action,
                     },
                 ))
@@ -258,179 +418,339 @@ fn despawn_all_animals(mut commands: Commands, animals: Query<Entity, With<Anima
     }
 }
 
-fn reproduce(
+pub fn conceive(
     mut commands: Commands,
-    config: Res<SimulationConfig>,
-    tile_layout: Res<TileLayout>,
-    animal_assets: Res<AnimalAssets>,
     animal_query: Query<
         (
-            &Mesh3d,
-            &Transform,
-            &Health,
-            &Age,
-            &mut SexualMaturity,
-            &EnergyData,
-            &OrganismEnergyEfficiency,
-            &AnimalEnergyEfficiency,
-            &ActionRange,
-            &Mobile,
-            &AttackDmg,
-            &SightRange,
-            &Diet,
+            (
+                &Health,
+                &Age,
+                &SexualMaturity,
+                &EnergyData,
+                &OrganismEnergyEfficiency,
+                &ThermalTolerance,
+            ),
+            (
+                &ActionRange,
+                &Mobile,
+                &AttackDmg,
+                &BiteSize,
+                &ToxinTolerance,
+                &SightRange,
+                &Digestion,
+                &Gestation,
+                &ParentalCare,
+            ),
+            (
+                &SpeciesId,
+                &Lineage,
+                &Senescence,
+                &DiseaseResistance,
+                Has<Pregnancy>,
+            ),
         ),
         With<AnimalMarker>,
     >,
-    mut tiles: Query<(&BiomeType, &mut ObjectsInTile)>,
+    sexes: Query<&Sex, With<AnimalMarker>>,
     mut event_reader: EventReader<ReproduceAnimalsEvent>,
 ) {
-    let mut choose_entity_observer = Observer::new(choose_entity_observer);
+    for event in event_reader.read() {
+        let (Ok(sex1), Ok(sex2)) = (sexes.get(event.parent1), sexes.get(event.parent2)) else {
+            continue;
+        };
+        let (mother_entity, father_entity) = match (sex1, sex2) {
+            (Sex::Female, Sex::Male) => (event.parent1, event.parent2),
+            (Sex::Male, Sex::Female) => (event.parent2, event.parent1),
+            _ => continue,
+        };
 
-    'outer: for event in event_reader.read() {
         let Ok((
-            mesh1,
-            transform1,
-            health1,
-            age1,
-            sexual_maturity1,
-            energy_data1,
-            organism_energy_efficiency1,
-            animal_energy_efficiency1,
-            action_range1,
-            mobile1,
-            attack1,
-            sight_range1,
-            diet1,
-        )) = animal_query.get(event.parent1)
+            (
+                mother_health,
+                mother_age,
+                mother_sexual_maturity,
+                mother_energy_data,
+                mother_organism_energy_efficiency,
+                mother_thermal_tolerance,
+            ),
+            (
+                mother_action_range,
+                mother_mobile,
+                mother_attack,
+                mother_bite_size,
+                mother_toxin_tolerance,
+                mother_sight_range,
+                mother_digestion,
+                mother_gestation,
+                mother_parental_care,
+            ),
+            (
+                mother_species_id,
+                _,
+                mother_senescence,
+                mother_disease_resistance,
+                mother_is_pregnant,
+            ),
+        )) = animal_query.get(mother_entity)
         else {
             continue;
         };
 
         let Ok((
-            _,
-            transform2,
-            health2,
-            age2,
-            sexual_maturity2,
-            energy_data2,
-            organism_energy_efficiency2,
-            animal_energy_efficiency2,
-            action_range2,
-            mobile2,
-            attack2,
-            sight_range2,
-            diet2,
-        )) = animal_query.get(event.parent2)
+            (
+                father_health,
+                father_age,
+                father_sexual_maturity,
+                father_energy_data,
+                father_organism_energy_efficiency,
+                father_thermal_tolerance,
+            ),
+            (
+                father_action_range,
+                father_mobile,
+                father_attack,
+                father_bite_size,
+                father_toxin_tolerance,
+                father_sight_range,
+                father_digestion,
+                father_gestation,
+                father_parental_care,
+            ),
+            (_, father_lineage, father_senescence, father_disease_resistance, _),
+        )) = animal_query.get(father_entity)
         else {
             continue;
         };
 
-        let starting_age = 0;
-        let diet = diet1.clone();
-        let animal_energy_efficiency = AnimalEnergyEfficiency::new();
-        let energy_data = EnergyData::new(
-            energy_data1
-                .max_active_energy_gene
-                .mixed_with(&energy_data2.max_active_energy_gene),
-            energy_data1
-                .energy_per_mass_unit_gene
-                .mixed_with(&energy_data2.energy_per_mass_unit_gene),
-            config.organism.starting_mass_dist.sample(),
-        );
-
-        // NOTE: position of a random parent "birthing", shouldn't be needed once we introduce sex
-        let point = RNG.with(|rng| {
-            let mut rng = rng.borrow_mut();
-            if rng.gen_bool(0.5) {
-                transform1.translation.truncate()
-            } else {
-                transform2.translation.truncate()
-            }
-        });
-
-        // NOTE: if animal is on the terrain it can't live on, we just don't spawn new animal.
-        let new_animal_position = tile_layout.get_random_position_in_ring(
-            point,
-            config.organism.offspring_spawn_range,
-            config.organism.offspring_spawn_range / 2.0,
-        );
-        let entity_of_tile_under = tile_layout.get_tile_entity_for_position(new_animal_position);
-        let (biome_under_new_animal, mut objects_in_tile) = tiles
-            .get_mut(entity_of_tile_under)
-            .expect("Failed to get tile components of tile for new animal position");
-        if !biome_under_new_animal.animals_can_live_here() {
-            continue 'outer;
+        // NOTE: mother could have already mated with someone else during this frame
+        if mother_is_pregnant {
+            continue;
         }
 
-        let new_size = energy_data.get_size();
-        let mut transform =
-            Transform::from_translation(new_animal_position.extend(energy_data.get_size() / 2.0))
-                .with_scale(Vec3::splat(new_size));
-        transform.translation.z = new_size / 2.0;
-
-        let new_entity = commands
-            .spawn((
-                BasicBundle {
-                    mesh: mesh1.clone(),
-                    material: MeshMaterial3d(get_animal_asset(&animal_assets, &diet)),
-                    transform,
-                },
-                AnimalBundle {
-                    organism_bundle: OrganismBundle {
-                        health: Health::new(health1.max_hp_gene.mixed_with(&health2.max_hp_gene)),
-                        age: Age::new(
-                            starting_age,
-                            age1.age_penalty_gene.mixed_with(&age2.age_penalty_gene),
-                        ),
-                        sexual_maturity: SexualMaturity::new(
-                            sexual_maturity1
-                                .maturity_age_gene
-                                .mixed_with(&sexual_maturity2.maturity_age_gene),
-                            sexual_maturity1
-                                .reproduction_cooldown_gene
-                                .mixed_with(&sexual_maturity2.reproduction_cooldown_gene),
-                            starting_age,
-                        ),
-                        energy_data,
-                        organism_energy_efficiency: OrganismEnergyEfficiency::new(
-                            organism_energy_efficiency1
-                                .energy_consumption_to_survive_per_mass_unit_gene
-                                .mixed_with(
-                                    &organism_energy_efficiency2
-                                        .energy_consumption_to_survive_per_mass_unit_gene,
-                                ),
-                            organism_energy_efficiency1
-                                .reproduction_energy_cost_gene
-                                .mixed_with(
-                                    &organism_energy_efficiency2.reproduction_energy_cost_gene,
-                                ),
-                        ),
-                    },
-                    marker: AnimalMarker,
-                    matter_marker: AnimalMatterMarker,
-                    animal_energy_efficiency,
-                    mobile: Mobile {
-                        speed: mobile1.speed.mixed_with(&mobile2.speed),
-                        destination: None,
-                        next_step_destination: None,
-                    },
+        let starting_age = 0;
+        // NOTE: old mothers conceive smaller litters
+        let fertility = mother_senescence.fertility.get_multiplier(mother_age);
+        // NOTE: each embryo gets its own mix of genes, so siblings differ
+        let embryos: Vec<_> = (0..mother_gestation.litter_size_gene.phenotype())
+            .filter(|_| rand::random::<f32>() < fertility)
+            .map(|_| {
+                let sex = Sex::random();
+
+                Embryo {
+                    health: mother_health.mixed_with(father_health),
+                    age: Age::new(
+                        starting_age,
+                        mother_age
+                            .age_penalty_gene
+                            .mixed_with(&father_age.age_penalty_gene),
+                    ),
+                    senescence: mother_senescence.mixed_with(father_senescence),
+                    sexual_maturity: mother_sexual_maturity
+                        .mixed_with(father_sexual_maturity, starting_age),
+                    max_active_energy_gene: mother_energy_data
+                        .max_active_energy_gene
+                        .mixed_with(&father_energy_data.max_active_energy_gene),
+                    energy_per_mass_unit_gene: mother_energy_data
+                        .energy_per_mass_unit_gene
+                        .mixed_with(&father_energy_data.energy_per_mass_unit_gene),
+                    organism_energy_efficiency: OrganismEnergyEfficiency::new(
+                        mother_organism_energy_efficiency
+                            .energy_consumption_to_survive_per_mass_unit_gene
+                            .mixed_with(
+                                &father_organism_energy_efficiency
+                                    .energy_consumption_to_survive_per_mass_unit_gene,
+                            ),
+                        mother_organism_energy_efficiency
+                            .reproduction_energy_cost_gene
+                            .mixed_with(
+                                &father_organism_energy_efficiency.reproduction_energy_cost_gene,
+                            ),
+                    ),
+                    thermal_tolerance: mother_thermal_tolerance
+                        .mixed_with(father_thermal_tolerance),
+                    disease_resistance: mother_disease_resistance
+                        .mixed_with(father_disease_resistance),
+                    speed_gene: mother_mobile.speed.mixed_with(&father_mobile.speed),
                     action_range: ActionRange {
-                        gene: action_range1.gene.mixed_with(&action_range2.gene),
+                        gene: mother_action_range
+                            .gene
+                            .mixed_with(&father_action_range.gene),
                     },
                     sight_range: SightRange {
-                        gene: sight_range1.gene.mixed_with(&sight_range2.gene),
+                        gene: mother_sight_range.gene.mixed_with(&father_sight_range.gene),
                     },
                     attack: AttackDmg {
-                        gene: attack1.gene.mixed_with(&attack2.gene),
+                        gene: mother_attack.gene.mixed_with(&father_attack.gene, sex),
+                    },
+                    bite_size: BiteSize {
+                        gene: mother_bite_size.gene.mixed_with(&father_bite_size.gene),
                     },
-                    // TODO: diet should also be a gene
-                    diet,
-                    action: Action::DoingNothing { for_hours: 0 },
-                },
-            ))
-            .id();
-        objects_in_tile.add_animal_entity(new_entity);
-        choose_entity_observer.watch_entity(new_entity);
+                    toxin_tolerance: ToxinTolerance {
+                        gene: mother_toxin_tolerance
+                            .gene
+                            .mixed_with(&father_toxin_tolerance.gene),
+                    },
+                    digestion: mother_digestion.mixed_with(father_digestion),
+                    gestation: mother_gestation.mixed_with(father_gestation),
+                    parental_care: mother_parental_care.mixed_with(father_parental_care),
+                    sex,
+                    species_id: *mother_species_id,
+                    father_lineage: father_lineage.clone(),
+                }
+            })
+            .collect();
+        if embryos.is_empty() {
+            continue;
+        }
+
+        commands.entity(mother_entity).try_insert(Pregnancy {
+            embryos,
+            time_units_left: mother_gestation.gestation_period_gene.phenotype(),
+        });
+    }
+}
+
+fn give_birth(
+    mut commands: Commands,
+    config: Res<SimulationConfig>,
+    tile_layout: Res<TileLayout>,
+    animal_assets: Res<AnimalAssets>,
+    mut offspring_lineage: OffspringLineage,
+    mut mothers: Query<
+        (
+            Entity,
+            &Mesh3d,
+            &Transform,
+            &mut Pregnancy,
+            &mut EnergyData,
+            &OrganismEnergyEfficiency,
+            &ParentalCare,
+            &Lineage,
+        ),
+        With<AnimalMarker>,
+    >,
+    mut tiles: Query<(&BiomeType, &mut ObjectsInTile)>,
+) {
+    let mut choose_entity_observer = Observer::new(choose_entity_observer);
+    let mut newborns_count = 0;
+
+    for (
+        mother_entity,
+        mesh,
+        mother_transform,
+        mut pregnancy,
+        mut mother_energy_data,
+        mother_organism_energy_efficiency,
+        mother_parental_care,
+        mother_lineage,
+    ) in mothers.iter_mut()
+    {
+        pregnancy.time_units_left = pregnancy.time_units_left.saturating_sub(1);
+        if pregnancy.time_units_left > 0 {
+            continue;
+        }
+
+        commands.entity(mother_entity).remove::<Pregnancy>();
+
+        let invested_energy = mother_organism_energy_efficiency
+            .reproduction_energy_cost_gene
+            .phenotype();
+        let care_duration = mother_parental_care.care_duration_gene.phenotype();
+
+        for embryo in pregnancy.embryos.drain(..) {
+            // NOTE: mother can't afford the rest of the litter, so it isn't born
+            if mother_energy_data
+                .try_to_consume_energy(invested_energy)
+                .is_err()
+            {
+                break;
+            }
+
+            // NOTE: if animal is on the terrain it can't live on, we just don't spawn new animal.
+            let new_animal_position = tile_layout.get_random_position_in_ring(
+                mother_transform.translation.truncate(),
+                config.organism.offspring_spawn_range,
+                config.organism.offspring_spawn_range / 2.0,
+            );
+            let entity_of_tile_under =
+                tile_layout.get_tile_entity_for_position(new_animal_position);
+            let (biome_under_new_animal, mut objects_in_tile) = tiles
+                .get_mut(entity_of_tile_under)
+                .expect("Failed to get tile components of tile for new animal position");
+            if !biome_under_new_animal.animals_can_live_here() {
+                continue;
+            }
+
+            let energy_data = EnergyData::from_invested_energy(
+                embryo.max_active_energy_gene,
+                embryo.energy_per_mass_unit_gene,
+                invested_energy,
+                config.animal.offspring_mass_energy_ratio,
+            );
+            let diet = embryo
+                .digestion
+                .get_diet(config.animal.diet_digestion_threshold);
+
+            let new_size = energy_data.get_size();
+            let transform = Transform::from_translation(new_animal_position.extend(new_size / 2.0))
+                .with_scale(Vec3::splat(new_size));
+
+            let new_entity = commands
+                .spawn((
+                    BasicBundle {
+                        mesh: mesh.clone(),
+                        material: MeshMaterial3d(get_animal_asset(&animal_assets, &diet)),
+                        transform,
+                    },
+                    AnimalBundle {
+                        organism_bundle: OrganismBundle {
+                            health: embryo.health,
+                            age: embryo.age,
+                            senescence: embryo.senescence,
+                            sexual_maturity: embryo.sexual_maturity,
+                            energy_data,
+                            organism_energy_efficiency: embryo.organism_energy_efficiency,
+                            thermal_tolerance: embryo.thermal_tolerance,
+                            disease_resistance: embryo.disease_resistance,
+                        },
+                        marker: AnimalMarker,
+                        matter_marker: AnimalMatterMarker,
+                        animal_energy_efficiency: AnimalEnergyEfficiency::new(),
+                        mobile: Mobile {
+                            speed: embryo.speed_gene,
+                            destination: None,
+                            next_step_destination: None,
+                        },
+                        action_range: embryo.action_range,
+                        sight_range: embryo.sight_range,
+                        attack: embryo.attack,
+                        bite_size: embryo.bite_size,
+                        toxin_tolerance: embryo.toxin_tolerance,
+                        digestion: embryo.digestion,
+                        gestation: embryo.gestation,
+                        parental_care: embryo.parental_care,
+                        diet,
+                        sex: embryo.sex,
+                        action: Action::DoingNothing { for_hours: 0 },
+                    },
+                    embryo.species_id,
+                    offspring_lineage.new_lineage(mother_lineage, &embryo.father_lineage),
+                ))
+                .id();
+            if config.animal.parental_care && care_duration > 0 {
+                commands.entity(new_entity).insert(CaredForBy {
+                    mother: mother_entity,
+                    time_units_left: care_duration,
+                });
+            }
+            objects_in_tile.add_animal_entity(new_entity);
+            choose_entity_observer.watch_entity(new_entity);
+            newborns_count += 1;
+        }
+    }
+
+    if newborns_count > 0 {
+        commands.spawn(choose_entity_observer);
     }
 }
 
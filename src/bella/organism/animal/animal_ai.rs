@@ -1,18 +1,25 @@
 use super::{
-    ActionRange, AnimalMarker, AnimalMatterMarker, AttackDmg, Diet, ReproduceAnimalsEvent,
-    SightRange,
+    parental_care::CaredForBy, ActionRange, AnimalMarker, AnimalMatterMarker, AttackDmg, BiteSize,
+    Diet, Digestion, Pregnancy, ReproduceAnimalsEvent, SightRange, ToxinTolerance,
 };
 use crate::bella::{
     config::SimulationConfig,
     organism::{
         carcass::Carcass,
         gene::FloatGene,
-        plant::{PlantMarker, PlantMatterMarker},
-        EnergyData, Health, HungerLevel, SexualMaturity,
+        plant::{
+            seed::{CarriedBy, Seed},
+            ChemicalDefense, PlantMarker, PlantMatterMarker,
+        },
+        species::SpeciesId,
+        Age, DamageSource, Energy, EnergyData, Health, HungerLevel, Senescence, Sex,
+        SexualMaturity,
     },
     pause::PauseState,
     restart::SimulationState,
-    terrain::{tile::TileLayout, BiomeType, ObjectsInTile},
+    terrain::{
+        spatial_query::SpatialQuery, tile::TileLayout, BiomeType, ObjectKind, ObjectsInTile,
+    },
     time::TimeUnitPassedEvent,
 };
 use bevy::prelude::*;
@@ -49,7 +56,19 @@ impl Plugin for AnimalAiPlugin {
                     .run_if(in_state(SimulationState::Simulation))
                     .run_if(in_state(PauseState::Running)),
             )
-            .add_systems(Update, handle_action);
+            .add_systems(
+                Update,
+                handle_action
+                    .run_if(in_state(SimulationState::Simulation))
+                    .run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(
+                Update,
+                take_bites
+                    .run_if(on_event::<TimeUnitPassedEvent>)
+                    .run_if(in_state(SimulationState::Simulation))
+                    .run_if(in_state(PauseState::Running)),
+            );
     }
 }
 
@@ -68,9 +87,25 @@ pub enum Action {
     Attacking {
         enemy: Entity,
     },
+    /// Takes a bite of a live plant, plant dies only when it's grazed below its minimum mass
+    Grazing {
+        plant: Entity,
+    },
     Mating {
         with: Entity,
     },
+    /// Juvenile under parental care goes back to its mother when it gets too far
+    FollowingMother {
+        mother: Entity,
+    },
+}
+
+impl Action {
+    /// Idling and wandering can be dropped for something more urgent (like defending juveniles),
+    /// the other actions are finished first
+    pub fn is_interruptible(&self) -> bool {
+        matches!(self, Action::DoingNothing { .. } | Action::GoingTo { .. })
+    }
 }
 
 #[derive(Event)]
@@ -104,21 +139,28 @@ pub fn discover_animal_state_and_set_action(
             &mut Action,
             &EnergyData,
             &SexualMaturity,
+            &Sex,
+            Has<Pregnancy>,
             &Diet,
             &SightRange,
+            (&Age, &Senescence),
+            Option<&CaredForBy>,
+            Option<&SpeciesId>,
         ),
         With<AnimalMarker>,
     >,
+    mothers_query: Query<(&Transform, &ActionRange), With<AnimalMarker>>,
     tile_layout: Res<TileLayout>,
-    objects_in_tile_query: Query<&ObjectsInTile>,
-    transforms_query: Query<&Transform>,
-    transforms_for_agressive_animals_query: Query<(
-        &Transform,
+    spatial_query: SpatialQuery,
+    potential_food_query: Query<(
         Option<&Diet>,
         Option<&PlantMatterMarker>,
         Option<&AnimalMatterMarker>,
     )>,
-    potential_partners_query: Query<(&Transform, &SexualMaturity), With<AnimalMarker>>,
+    potential_partners_query: Query<
+        (&SexualMaturity, &Sex, Option<&SpeciesId>),
+        (With<AnimalMarker>, Without<Pregnancy>),
+    >,
 ) {
     'main_loop: for (event, _) in event_reader.par_read() {
         let Ok((
@@ -127,51 +169,66 @@ pub fn discover_animal_state_and_set_action(
             mut animal_action,
             energy_data,
             sexual_maturity,
+            sex,
+            is_pregnant,
             animal_diet,
             sight_range,
+            (age, senescence),
+            cared_for_by,
+            species_id,
         )) = animals_query.get_mut(event.animal_entity)
         else {
             continue;
         };
+        let sight_range = sight_range.gene.phenotype() * senescence.sight.get_multiplier(age);
 
         // TODO: here we should have something like CharacterComponent, that allows us to value each of those states in range [0; 1] and then we choose the most important one for that animal (like aggressive animals, horny etc.)
 
         let is_hungry = matches!(energy_data.get_hunger_level(), HungerLevel::Hungry);
+        let animal_position = animal_transform.translation.truncate();
+
+        'following_mother: {
+            let Some(cared_for_by) = cared_for_by else {
+                break 'following_mother;
+            };
+            let Ok((mother_transform, mother_action_range)) =
+                mothers_query.get(cared_for_by.mother)
+            else {
+                break 'following_mother;
+            };
+
+            // NOTE: juvenile close to its mother can do other things, like eating
+            if tile_layout.get_distance(mother_transform.translation.truncate(), animal_position)
+                < mother_action_range.gene.phenotype()
+            {
+                break 'following_mother;
+            }
 
+            *animal_action = Action::FollowingMother {
+                mother: cared_for_by.mother,
+            };
+            continue 'main_loop;
+        }
         'horny: {
-            if !sexual_maturity.is_ready_to_reproduce() {
+            if !sexual_maturity.is_ready_to_reproduce() || is_pregnant {
                 break 'horny;
             }
 
-            let chosen_partner_entity = tile_layout
-                .get_tile_entities_in_range(
-                    animal_transform.translation.truncate(),
-                    sight_range.gene.phenotype(),
-                )
-                .iter()
-                // NOTE: we take all animals, maybe we should filter by diet (i.e. species once they are introduced)
-                .flat_map(|tile_entity| {
-                    objects_in_tile_query
-                        .get(*tile_entity)
-                        .expect("Failed to get tile")
-                        .animals
-                        .clone()
-                })
-                .filter_map(|entity| match potential_partners_query.get(entity) {
-                    Ok((transform, sexual_maturity)) => {
-                        if sexual_maturity.is_ready_to_reproduce() {
-                            let distance =
-                                animal_transform.translation.distance(transform.translation);
-
-                            Some((entity, distance))
-                        } else {
-                            None
-                        }
-                    }
-                    Err(_) => None,
-                })
-                .max_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
-                .map(|(entity, _)| entity);
+            let chosen_partner_entity = spatial_query.find_nearest(
+                animal_position,
+                sight_range,
+                &[ObjectKind::Animal],
+                |other_entity| {
+                    other_entity != entity
+                        && potential_partners_query.get(other_entity).is_ok_and(
+                            |(sexual_maturity, other_sex, other_species_id)| {
+                                sexual_maturity.is_ready_to_reproduce()
+                                    && Sex::can_mate(Some(sex), Some(other_sex))
+                                    && species_id == other_species_id
+                            },
+                        )
+                },
+            );
 
             match chosen_partner_entity {
                 Some(partner_entity) => {
@@ -188,28 +245,18 @@ pub fn discover_animal_state_and_set_action(
                 break 'hungry_non_agressive;
             }
 
-            let chosen_food_entity = tile_layout
-                .get_tile_entities_in_range(
-                    animal_transform.translation.truncate(),
-                    sight_range.gene.phenotype(),
-                )
-                .iter()
-                .map(|tile_entity| objects_in_tile_query.get(*tile_entity).expect("Failed to get tile"))
-                .flat_map(|objects_in_tile| match animal_diet {
-                    Diet::Carnivore => objects_in_tile.animal_carcasses.clone(),
-                    Diet::Herbivore => [objects_in_tile.plant_carcasses.clone()].concat(),
-                    Diet::Omnivore => [objects_in_tile.plant_carcasses.clone(), objects_in_tile.animal_carcasses.clone()].concat(),
-                })
-                .map(|entity| {
-                    let transform = transforms_query
-                        .get(entity)
-                        .expect("Failed to get transform for entity despite that entity being in ObjectsInTile");
-                    let distance = animal_transform.translation.distance(transform.translation);
-
-                    (entity, distance)
-                })
-                .max_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
-                .map(|(entity, _)| entity);
+            let carcass_kinds: &[ObjectKind] = match animal_diet {
+                Diet::Carnivore => &[ObjectKind::AnimalCarcass],
+                Diet::Herbivore => &[ObjectKind::PlantCarcass, ObjectKind::Seed],
+                Diet::Omnivore => &[
+                    ObjectKind::PlantCarcass,
+                    ObjectKind::Seed,
+                    ObjectKind::AnimalCarcass,
+                ],
+            };
+
+            let chosen_food_entity =
+                spatial_query.find_nearest(animal_position, sight_range, carcass_kinds, |_| true);
 
             match chosen_food_entity {
                 Some(food_entity) => {
@@ -226,61 +273,66 @@ pub fn discover_animal_state_and_set_action(
                 break 'hungry_agressive;
             }
 
-            let chosen_prey_entity = tile_layout
-                .get_tile_entities_in_range(
-                    animal_transform.translation.truncate(),
-                    sight_range.gene.phenotype(),
-                )
-                .iter()
-                .map(|tile_entity| {
-                    objects_in_tile_query
-                        .get(*tile_entity)
-                        .expect("Failed to get tile")
-                })
-                .flat_map(|objects_in_tile| match animal_diet {
-                    Diet::Carnivore => objects_in_tile.animals.clone(),
-                    Diet::Herbivore => objects_in_tile.plants.clone(),
-                    Diet::Omnivore => [
-                        objects_in_tile.plants.clone(),
-                        objects_in_tile.animals.clone(),
-                    ]
-                    .concat(),
-                })
-                // HACK: sometimes this entity isn't in transform query for some reason, so we just ignore it
-                .flat_map(|entity| match transforms_for_agressive_animals_query.get(entity) {
-                    Ok((transform, diet, plant_marker, animal_marker)) => {
-                        match (animal_diet, diet, plant_marker, animal_marker){
-                            // herbivores only eat plants and dead plant matter
-                            (Diet::Herbivore, None, Some(_), None)  |
-                            // carnivores only eat harbivores and dead animal matter
-                            (Diet::Carnivore, Some(Diet::Herbivore), None, Some(_))  | (Diet::Carnivore, None, None, Some(_)) |
-                            // omnivores only eat herbivores, plants, and dead matter
-                            (Diet::Omnivore, Some(Diet::Herbivore), None, Some(_)) | (Diet::Omnivore, None, _, _) => {
-                                let distance = animal_transform.translation.distance(transform.translation);
+            let prey_kinds: &[ObjectKind] = match animal_diet {
+                Diet::Carnivore => &[ObjectKind::Animal],
+                Diet::Herbivore => &[ObjectKind::Plant],
+                Diet::Omnivore => &[ObjectKind::Plant, ObjectKind::Animal],
+            };
 
-                                Some((entity, distance))
-                            }
-                            _ => None,
-                        }
+            let chosen_prey_entity = spatial_query.find_nearest(
+                animal_position,
+                sight_range,
+                prey_kinds,
+                |other_entity| {
+                    if other_entity == entity {
+                        return false;
                     }
-                    Err(_) => None,
-                })
-            .max_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
-            .map(|(entity, _)| entity);
+
+                    // HACK: sometimes this entity isn't in the query for some reason, so we just ignore it
+                    let Ok((diet, plant_marker, animal_marker)) =
+                        potential_food_query.get(other_entity)
+                    else {
+                        return false;
+                    };
+
+                    matches!(
+                        (animal_diet, diet, plant_marker, animal_marker),
+                        // herbivores only eat plants and dead plant matter
+                        (Diet::Herbivore, None, Some(_), None)
+                            // carnivores only eat harbivores and dead animal matter
+                            | (Diet::Carnivore, Some(Diet::Herbivore), None, Some(_))
+                            | (Diet::Carnivore, None, None, Some(_))
+                            // omnivores only eat herbivores, plants, and dead matter
+                            | (Diet::Omnivore, Some(Diet::Herbivore), None, Some(_))
+                            | (Diet::Omnivore, None, _, _)
+                    )
+                },
+            );
 
             match chosen_prey_entity {
                 Some(prey_entity) => {
-                    *animal_action = Action::Attacking { enemy: prey_entity };
+                    // NOTE: live plants are grazed instead of attacked
+                    let is_plant = potential_food_query
+                        .get(prey_entity)
+                        .is_ok_and(|(_, plant_marker, _)| plant_marker.is_some());
+                    *animal_action = if is_plant {
+                        Action::Grazing { plant: prey_entity }
+                    } else {
+                        Action::Attacking { enemy: prey_entity }
+                    };
                     continue 'main_loop;
                 }
                 None => break 'hungry_agressive,
             };
         }
         'bored: {
-            let wander_around_to = tile_layout.get_random_position_in_range(
-                animal_transform.translation.truncate(),
-                sight_range.gene.phenotype(),
-            );
+            // NOTE: juveniles under parental care don't wander away from their mother
+            if cared_for_by.is_some() {
+                break 'bored;
+            }
+
+            let wander_around_to = tile_layout
+                .get_random_position_in_range(animal_transform.translation.truncate(), sight_range);
 
             *animal_action = Action::GoingTo {
                 position: wander_around_to,
@@ -289,19 +341,25 @@ pub fn discover_animal_state_and_set_action(
     }
 }
 
-fn handle_action(
+/// Moves animals towards the targets of their actions and attacks or mates once they are in range.
+/// Eating and grazing bites are taken in `take_bites`.
+pub fn handle_action(
     config: Res<SimulationConfig>,
-    mut animals_query: Query<(
-        Entity,
-        &mut Action,
-        &mut Mobile,
-        &ActionRange,
-        &Transform,
-        &AttackDmg,
-        &mut EnergyData,
-        &mut SexualMaturity,
-    )>,
-    mut matter_query: Query<(&mut Carcass, &Transform)>,
+    tile_layout: Res<TileLayout>,
+    mut animals_query: Query<
+        (
+            Entity,
+            &mut Action,
+            &mut Mobile,
+            &ActionRange,
+            &Transform,
+            &AttackDmg,
+            (&mut SexualMaturity, Has<Pregnancy>),
+            (&Age, &Senescence),
+        ),
+        With<AnimalMarker>,
+    >,
+    food_query: Query<&Transform, (Or<(With<Carcass>, With<Seed>)>, Without<CarriedBy>)>,
     mut other_organism_query: Query<
         (&mut Health, &Transform),
         (
@@ -312,7 +370,7 @@ fn handle_action(
     mut reproduction_ew: EventWriter<ReproduceAnimalsEvent>,
     mut other_animal_partner_query: Query<&Transform, With<AnimalMarker>>,
 ) {
-    let mut animals_that_will_reproduce = Vec::new();
+    let mut mating_pairs = Vec::new();
 
     for (
         animal_entity,
@@ -321,8 +379,8 @@ fn handle_action(
         action_range,
         transform,
         attack,
-        mut energy_data,
         _,
+        (age, senescence),
     ) in animals_query.iter_mut()
     {
         match *action {
@@ -335,35 +393,51 @@ fn handle_action(
                 }
             }
             Action::Eating { food: food_entity } => {
-                // NOTE: carcass entity could have already disappeared, just ignore it
-                let Ok((mut carcass, carcass_transform)) = matter_query.get_mut(food_entity) else {
+                // NOTE: seed or carcass could have already disappeared or been picked up, just ignore it
+                let Ok(food_transform) = food_query.get(food_entity) else {
                     *action = Action::DoingNothing { for_hours: 0 };
                     continue;
                 };
 
-                if carcass_transform
-                    .translation
-                    .distance(transform.translation)
-                    < action_range.gene.phenotype()
+                if tile_layout.get_distance(
+                    food_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                ) >= action_range.gene.phenotype()
                 {
-                    if carcass.mass <= 0.0{
-                        continue;
-                    }
-                    let mut eaten_mass = attack.gene.phenotype(); // NOTE: that probably should be a different parameter?
-                    if eaten_mass > carcass.mass {
-                        eaten_mass = carcass.mass;
-                    }
-                    carcass.mass -= eaten_mass;
-                    energy_data.store_energy(eaten_mass * carcass.energy_per_mass_unit);
-                } else {
                     mobile.destination = Some(Destination::Organism {
                         entity: food_entity,
                     });
                 }
             }
+            Action::Grazing {
+                plant: plant_entity,
+            } => {
+                // NOTE: plant could have already died, just ignore it
+                let Ok((_, plant_transform)) = other_organism_query.get(plant_entity) else {
+                    *action = Action::DoingNothing { for_hours: 0 };
+                    continue;
+                };
+
+                if tile_layout.get_distance(
+                    plant_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                ) >= action_range.gene.phenotype()
+                {
+                    mobile.destination = Some(Destination::Organism {
+                        entity: plant_entity,
+                    });
+                }
+            }
             Action::Attacking {
                 enemy: enemy_entity,
             } => {
+                let attack_multiplier = senescence.attack.get_multiplier(age)
+                    * other_organism_query
+                        .get(animal_entity)
+                        .map_or(1.0, |(health, _)| {
+                            health.get_injury_multiplier(config.organism.max_injury_penalty)
+                        });
+
                 // NOTE: entity could have already became something else like carcass, just ignore it
                 let Ok((mut health, other_transform)) = other_organism_query.get_mut(enemy_entity)
                 else {
@@ -371,10 +445,15 @@ fn handle_action(
                     continue;
                 };
 
-                if other_transform.translation.distance(transform.translation)
-                    < action_range.gene.phenotype()
+                if tile_layout.get_distance(
+                    other_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                ) < action_range.gene.phenotype()
                 {
-                    health.hp -= attack.gene.phenotype();
+                    health.take_damage(
+                        attack.gene.phenotype() * attack_multiplier,
+                        DamageSource::Predation,
+                    );
                 } else {
                     mobile.destination = Some(Destination::Organism {
                         entity: enemy_entity,
@@ -390,31 +469,204 @@ fn handle_action(
                     continue;
                 };
 
-                if other_transform.translation.distance(transform.translation)
-                    < action_range.gene.phenotype()
+                if tile_layout.get_distance(
+                    other_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                ) < action_range.gene.phenotype()
                 {
-                    reproduction_ew.send(ReproduceAnimalsEvent {
-                        parent1: animal_entity,
-                        parent2: partner_entity,
-                    });
-                    animals_that_will_reproduce.push(animal_entity);
-                    animals_that_will_reproduce.push(partner_entity);
+                    mating_pairs.push((animal_entity, partner_entity));
+                    *action = Action::DoingNothing { for_hours: 0 };
                 } else {
                     mobile.destination = Some(Destination::Organism {
                         entity: partner_entity,
                     });
                 }
             }
+            Action::FollowingMother {
+                mother: mother_entity,
+            } => {
+                // NOTE: mother could have already died, just ignore her
+                let Ok(mother_transform) = other_animal_partner_query.get(mother_entity) else {
+                    *action = Action::DoingNothing { for_hours: 0 };
+                    continue;
+                };
+
+                if tile_layout.get_distance(
+                    mother_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                ) < action_range.gene.phenotype()
+                {
+                    *action = Action::DoingNothing { for_hours: 0 };
+                } else {
+                    mobile.destination = Some(Destination::Organism {
+                        entity: mother_entity,
+                    });
+                }
+            }
         }
     }
 
-    animals_that_will_reproduce
-        .into_iter()
-        .for_each(|animal_entity| {
-            if let Ok(mut animal) = animals_query.get_mut(animal_entity) {
-                animal.7.reset_reproduction_cooldown();
-            }
+    for (animal_entity, partner_entity) in mating_pairs {
+        // NOTE: partner could have already mated with someone else during this frame, or got pregnant since it was chosen
+        let both_ready = [animal_entity, partner_entity].iter().all(|&entity| {
+            animals_query.get(entity).is_ok_and(
+                |(_, _, _, _, _, _, (sexual_maturity, is_pregnant), _)| {
+                    sexual_maturity.is_ready_to_reproduce() && !is_pregnant
+                },
+            )
+        });
+        if !both_ready {
+            continue;
+        }
+
+        reproduction_ew.send(ReproduceAnimalsEvent {
+            parent1: animal_entity,
+            parent2: partner_entity,
         });
+        for entity in [animal_entity, partner_entity] {
+            if let Ok((_, _, _, _, _, _, (mut sexual_maturity, _), _)) =
+                animals_query.get_mut(entity)
+            {
+                sexual_maturity.register_reproduction();
+            }
+        }
+    }
+}
+
+/// Animals that are eating or grazing take one bite per time unit, once they are in range of their food
+fn take_bites(
+    config: Res<SimulationConfig>,
+    tile_layout: Res<TileLayout>,
+    mut animals_query: Query<
+        (
+            Entity,
+            &mut Action,
+            &ActionRange,
+            &Transform,
+            &mut EnergyData,
+            &Digestion,
+            &BiteSize,
+            &ToxinTolerance,
+        ),
+        With<AnimalMarker>,
+    >,
+    mut plants_query: Query<
+        (&mut EnergyData, &Transform, &ChemicalDefense),
+        (With<PlantMarker>, Without<AnimalMarker>),
+    >,
+    mut matter_query: Query<(&mut Carcass, &Transform, Has<AnimalMatterMarker>)>,
+    mut seed_query: Query<(&mut Seed, &Transform), Without<CarriedBy>>,
+    mut healths: Query<&mut Health>,
+) {
+    for (
+        animal_entity,
+        mut action,
+        action_range,
+        transform,
+        mut energy_data,
+        digestion,
+        bite_size,
+        toxin_tolerance,
+    ) in animals_query.iter_mut()
+    {
+        match *action {
+            Action::Eating { food: food_entity } => {
+                if let Ok((mut seed, seed_transform)) = seed_query.get_mut(food_entity) {
+                    if tile_layout.get_distance(
+                        seed_transform.translation.truncate(),
+                        transform.translation.truncate(),
+                    ) < action_range.gene.phenotype()
+                    {
+                        let eaten_mass = bite_size.gene.phenotype().min(seed.energy_data.mass);
+                        seed.energy_data.mass -= eaten_mass;
+                        let (energy, poisoning_damage) = digest_plant_matter(
+                            eaten_mass,
+                            seed.energy_data.energy_per_mass_unit_gene.phenotype(),
+                            &seed.chemical_defense,
+                            toxin_tolerance,
+                            digestion,
+                            &config,
+                        );
+                        energy_data.store_energy(energy);
+                        if poisoning_damage > 0.0 {
+                            if let Ok(mut health) = healths.get_mut(animal_entity) {
+                                health.take_damage(poisoning_damage, DamageSource::Poisoning);
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                // NOTE: carcass entity could have already disappeared, just ignore it
+                let Ok((mut carcass, carcass_transform, is_meat)) =
+                    matter_query.get_mut(food_entity)
+                else {
+                    continue;
+                };
+
+                if tile_layout.get_distance(
+                    carcass_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                ) < action_range.gene.phenotype()
+                {
+                    if carcass.mass <= 0.0{
+                        continue;
+                    }
+                    let eaten_mass = bite_size.gene.phenotype().min(carcass.mass);
+                    carcass.mass -= eaten_mass;
+                    energy_data.store_energy(
+                        eaten_mass
+                            * carcass.energy_per_mass_unit
+                            * digestion.get_efficiency(is_meat),
+                    );
+                }
+            }
+            Action::Grazing {
+                plant: plant_entity,
+            } => {
+                // NOTE: plant could have already died, just ignore it
+                let Ok((mut plant_energy_data, plant_transform, chemical_defense)) =
+                    plants_query.get_mut(plant_entity)
+                else {
+                    continue;
+                };
+
+                if tile_layout.get_distance(
+                    plant_transform.translation.truncate(),
+                    transform.translation.truncate(),
+                ) >= action_range.gene.phenotype()
+                {
+                    continue;
+                }
+
+                let grazed_mass = bite_size.gene.phenotype().min(plant_energy_data.mass);
+                plant_energy_data.mass -= grazed_mass;
+                let (energy, poisoning_damage) = digest_plant_matter(
+                    grazed_mass,
+                    plant_energy_data.energy_per_mass_unit_gene.phenotype(),
+                    chemical_defense,
+                    toxin_tolerance,
+                    digestion,
+                    &config,
+                );
+                energy_data.store_energy(energy);
+                if poisoning_damage > 0.0 {
+                    if let Ok(mut health) = healths.get_mut(animal_entity) {
+                        health.take_damage(poisoning_damage, DamageSource::Poisoning);
+                    }
+                }
+
+                if plant_energy_data.mass < config.plant.min_mass {
+                    if let Ok(mut plant_health) = healths.get_mut(plant_entity) {
+                        let hp = plant_health.hp.max(0.0);
+                        plant_health.take_damage(hp, DamageSource::Predation);
+                    }
+                    *action = Action::DoingNothing { for_hours: 0 };
+                }
+            }
+            _ => (),
+        }
+    }
 }
 
 #[derive(Component, Reflect, Debug)]
@@ -430,6 +682,24 @@ pub enum Destination {
     Organism { entity: Entity },
 }
 
+/// Energy the eater gets from plant matter (live plant or seed) and poisoning damage it takes.
+/// Toxins that the eater doesn't tolerate lower the energy and poison it.
+fn digest_plant_matter(
+    eaten_mass: f32,
+    energy_per_mass_unit: Energy,
+    chemical_defense: &ChemicalDefense,
+    toxin_tolerance: &ToxinTolerance,
+    digestion: &Digestion,
+    config: &SimulationConfig,
+) -> (Energy, f32) {
+    let toxicity = chemical_defense.get_effective_toxicity(toxin_tolerance.gene.phenotype());
+    let energy =
+        eaten_mass * energy_per_mass_unit * digestion.get_efficiency(false) * (1.0 - toxicity);
+    let poisoning_damage = eaten_mass * toxicity * config.plant.toxin_damage_per_mass_unit;
+
+    (energy, poisoning_damage)
+}
+
 pub fn find_next_step_destination(
     query_organisms: Query<&Transform>,
     mut query_mobiles: Query<&mut Mobile>,
@@ -448,35 +718,50 @@ pub fn find_next_step_destination(
 }
 
 pub fn make_step(
-    mut query: Query<(Entity, &mut Mobile, &mut Transform)>,
+    mut query: Query<(
+        Entity,
+        &mut Mobile,
+        &mut Transform,
+        &Health,
+        &Age,
+        &Senescence,
+    )>,
+    config: Res<SimulationConfig>,
     tile_layout: Res<TileLayout>,
     mut objects_in_tiles: Query<&mut ObjectsInTile>,
     biome_types: Query<&BiomeType>,
 ) {
-    for (entity, mut mobile, mut transform) in query.iter_mut() {
+    for (entity, mut mobile, mut transform, health, age, senescence) in query.iter_mut() {
         let Some(dest_position) = mobile.next_step_destination else {
             continue;
         };
         let prev_position = transform.translation.truncate();
-        let position_diff = dest_position - prev_position;
+        let position_diff = tile_layout.get_displacement(prev_position, dest_position);
+        let speed = mobile.speed.phenotype()
+            * health.get_injury_multiplier(config.organism.max_injury_penalty)
+            * senescence.speed.get_multiplier(age);
 
-        let move_by = if position_diff.length() <= mobile.speed.phenotype() {
+        let move_by = if position_diff.length() <= speed {
             mobile.destination = None;
             position_diff
         } else {
-            position_diff.normalize() * mobile.speed.phenotype()
+            position_diff.normalize() * speed
         };
 
-        let new_position = prev_position + move_by;
+        let new_position = tile_layout.wrap_position(prev_position + move_by);
         let prev_tile_entity = tile_layout.get_tile_entity_for_position(prev_position);
-        let next_tile_entity = tile_layout.get_tile_entity_for_position(new_position);
+        let Some(next_tile_entity) = tile_layout.try_get_tile_entity_for_position(new_position)
+        else {
+            mobile.destination = None;
+            continue;
+        };
 
         let animal_can_live_here = biome_types
             .get(next_tile_entity)
             .expect("Failed to get next tile biome")
             .animals_can_live_here();
 
-        if tile_layout.is_position_in_bounds(new_position) && animal_can_live_here {
+        if animal_can_live_here {
             transform.translation.x = new_position.x;
             transform.translation.y = new_position.y;
             mobile.next_step_destination = None;
@@ -0,0 +1,159 @@
+use super::{animal_ai::Action, ActionRange, AnimalMarker, SightRange};
+use crate::bella::{
+    config::SimulationConfig,
+    organism::{
+        gene::IntGene, Age, EnergyData, HungerLevel, Senescence, SexualMaturity,
+        SexualMaturityLevel,
+    },
+    restart::SimulationState,
+    terrain::tile::TileLayout,
+    time::TimeUnitPassedEvent,
+};
+use bevy::prelude::*;
+
+pub struct ParentalCarePlugin;
+
+impl Plugin for ParentalCarePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ParentalCare>()
+            .register_type::<CaredForBy>()
+            .add_systems(
+                Update,
+                (care_for_juveniles, defend_juveniles)
+                    .run_if(on_event::<TimeUnitPassedEvent>)
+                    .run_if(in_state(SimulationState::Simulation)),
+            );
+    }
+}
+
+/// How long mother takes care of her offspring, only used when `parental_care` is enabled in animal config
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct ParentalCare {
+    /// in time units
+    pub care_duration_gene: IntGene,
+}
+
+impl ParentalCare {
+    pub fn new(care_duration_gene: IntGene) -> Self {
+        Self { care_duration_gene }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(
+            self.care_duration_gene
+                .mixed_with(&other.care_duration_gene),
+        )
+    }
+}
+
+/// Juvenile follows its mother, who feeds it and defends it from predators.
+/// Care ends when `time_units_left` reaches `0`, the juvenile matures or the mother dies.
+#[derive(Component, Reflect, Debug)]
+pub struct CaredForBy {
+    pub mother: Entity,
+    pub time_units_left: u32,
+}
+
+fn care_for_juveniles(
+    mut commands: Commands,
+    config: Res<SimulationConfig>,
+    tile_layout: Res<TileLayout>,
+    mut juveniles: Query<
+        (
+            Entity,
+            &mut CaredForBy,
+            &Transform,
+            &SexualMaturity,
+            &mut EnergyData,
+        ),
+        With<AnimalMarker>,
+    >,
+    mut mothers: Query<
+        (&Transform, &ActionRange, &mut EnergyData),
+        (With<AnimalMarker>, Without<CaredForBy>),
+    >,
+) {
+    for (juvenile_entity, mut cared_for_by, transform, sexual_maturity, mut energy_data) in
+        juveniles.iter_mut()
+    {
+        cared_for_by.time_units_left = cared_for_by.time_units_left.saturating_sub(1);
+
+        let is_young = matches!(sexual_maturity.level, SexualMaturityLevel::Young { .. });
+        let mother = mothers.get_mut(cared_for_by.mother);
+        let Ok((mother_transform, mother_action_range, mut mother_energy_data)) = mother else {
+            commands.entity(juvenile_entity).remove::<CaredForBy>();
+            continue;
+        };
+        if cared_for_by.time_units_left == 0 || !is_young {
+            commands.entity(juvenile_entity).remove::<CaredForBy>();
+            continue;
+        }
+
+        // NOTE: mother feeds the juvenile only if she isn't hungry herself
+        let is_juvenile_hungry = matches!(energy_data.get_hunger_level(), HungerLevel::Hungry);
+        let is_mother_satiated =
+            matches!(mother_energy_data.get_hunger_level(), HungerLevel::Satiated);
+        let is_close_to_mother = tile_layout.get_distance(
+            mother_transform.translation.truncate(),
+            transform.translation.truncate(),
+        ) < mother_action_range.gene.phenotype();
+
+        if is_juvenile_hungry && is_mother_satiated && is_close_to_mother {
+            let energy = config.animal.parental_feeding_energy;
+            if mother_energy_data.try_to_consume_energy(energy).is_ok() {
+                energy_data.store_energy(energy);
+            }
+        }
+    }
+}
+
+/// Mother attacks predators attacking her juveniles, if she can see them and isn't busy with something else
+fn defend_juveniles(
+    tile_layout: Res<TileLayout>,
+    mut animals: Query<
+        (
+            Entity,
+            &mut Action,
+            &Transform,
+            &SightRange,
+            &Age,
+            &Senescence,
+        ),
+        With<AnimalMarker>,
+    >,
+    juveniles: Query<&CaredForBy, With<AnimalMarker>>,
+) {
+    let mut defenses = Vec::new();
+    for (predator_entity, action, _, _, _, _) in animals.iter() {
+        if let Action::Attacking { enemy } = *action {
+            if let Ok(cared_for_by) = juveniles.get(enemy) {
+                defenses.push((cared_for_by.mother, predator_entity));
+            }
+        }
+    }
+
+    for (mother_entity, predator_entity) in defenses {
+        let Ok(predator_position) = animals
+            .get(predator_entity)
+            .map(|(_, _, transform, _, _, _)| transform.translation.truncate())
+        else {
+            continue;
+        };
+        let Ok((_, mut action, transform, sight_range, age, senescence)) =
+            animals.get_mut(mother_entity)
+        else {
+            continue;
+        };
+        if !action.is_interruptible() {
+            continue;
+        }
+
+        if tile_layout.get_distance(transform.translation.truncate(), predator_position)
+            < sight_range.gene.phenotype() * senescence.sight.get_multiplier(age)
+        {
+            *action = Action::Attacking {
+                enemy: predator_entity,
+            };
+        }
+    }
+}
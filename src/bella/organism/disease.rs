@@ -0,0 +1,296 @@
+use super::{
+    animal::{animal_ai::Action, ActionRange, AnimalMarker},
+    gene::FloatGene,
+    plant::PlantMarker,
+    DamageSource, EnergyData, Health,
+};
+use crate::bella::{
+    config::SimulationConfig,
+    restart::SimulationState,
+    terrain::{tile::TileLayout, ObjectsInTile},
+    time::TimeUnitPassedEvent,
+};
+use bevy::{prelude::*, utils::HashSet};
+
+pub struct DiseasePlugin;
+
+impl Plugin for DiseasePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DiseaseResistance>()
+            .register_type::<Infection>()
+            .register_type::<InfectionStage>()
+            .add_event::<InfectionTransmissionEvent>()
+            .add_systems(
+                OnEnter(SimulationState::PreSimulation),
+                infect_starting_population,
+            )
+            .add_systems(
+                Update,
+                (
+                    (spread_by_contact, spread_by_predation_and_eating),
+                    infect,
+                    progress_infections,
+                )
+                    .chain()
+                    .run_if(on_event::<TimeUnitPassedEvent>)
+                    .run_if(in_state(SimulationState::Simulation)),
+            );
+    }
+}
+
+/// Chance of fighting off the infection, both when exposed to it and when sick
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct DiseaseResistance {
+    pub gene: FloatGene,
+}
+
+impl DiseaseResistance {
+    pub fn new(gene: FloatGene) -> Self {
+        Self { gene }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(self.gene.mixed_with(&other.gene))
+    }
+}
+
+/// Stays on the carcass after death, so eating it can spread the infection
+#[derive(Component, Reflect, Debug)]
+pub struct Infection {
+    pub stage: InfectionStage,
+    pub time_units_left: u32,
+}
+
+impl Infection {
+    fn new(config: &SimulationConfig) -> Self {
+        Self {
+            stage: InfectionStage::Incubating,
+            time_units_left: config.disease.incubation_time,
+        }
+    }
+
+    /// Only sick organisms pass the infection to others, carcasses are always infectious
+    pub fn is_infectious(&self) -> bool {
+        matches!(self.stage, InfectionStage::Sick)
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfectionStage {
+    /// infected, but without symptoms and not infectious yet
+    Incubating,
+    /// loses energy every time unit and infects others
+    Sick,
+}
+
+impl InfectionStage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            InfectionStage::Incubating => "i",
+            InfectionStage::Sick => "s",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransmissionRoute {
+    Contact,
+    Predation,
+    CarcassEating,
+}
+
+/// Organism was exposed to the infection, it can still resist it
+#[derive(Event, Debug)]
+pub struct InfectionTransmissionEvent {
+    pub entity: Entity,
+    pub route: TransmissionRoute,
+}
+
+fn infect_starting_population(
+    mut commands: Commands,
+    organisms: Query<
+        Entity,
+        (
+            Or<(With<AnimalMarker>, With<PlantMarker>)>,
+            Without<Infection>,
+        ),
+    >,
+    config: Res<SimulationConfig>,
+) {
+    for entity in organisms.iter() {
+        if rand::random::<f32>() < config.disease.initial_infection_chance {
+            commands.entity(entity).insert(Infection::new(&config));
+        }
+    }
+}
+
+/// Sick organisms can infect every organism of the same kind (animal or plant) on their tile
+fn spread_by_contact(
+    tiles: Query<&ObjectsInTile>,
+    infections: Query<&Infection, Or<(With<AnimalMarker>, With<PlantMarker>)>>,
+    mut event_writer: EventWriter<InfectionTransmissionEvent>,
+    config: Res<SimulationConfig>,
+) {
+    for objects_in_tile in tiles.iter() {
+        for organisms in [&objects_in_tile.animals, &objects_in_tile.plants] {
+            let infectious_count = organisms
+                .iter()
+                .filter(|&&entity| infections.get(entity).is_ok_and(Infection::is_infectious))
+                .count();
+            if infectious_count == 0 {
+                continue;
+            }
+
+            // NOTE: each infectious organism gets its own chance to infect
+            let chance = 1.0
+                - (1.0 - config.disease.contact_transmission_chance).powi(infectious_count as i32);
+            for &entity in organisms {
+                if !infections.contains(entity) && rand::random::<f32>() < chance {
+                    event_writer.send(InfectionTransmissionEvent {
+                        entity,
+                        route: TransmissionRoute::Contact,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Attacks and grazing spread the infection both ways, eating infected carcass infects the eater
+fn spread_by_predation_and_eating(
+    animals: Query<(Entity, &Action, &Transform, &ActionRange), With<AnimalMarker>>,
+    infections: Query<&Infection>,
+    transforms: Query<&Transform>,
+    mut event_writer: EventWriter<InfectionTransmissionEvent>,
+    tile_layout: Res<TileLayout>,
+    config: Res<SimulationConfig>,
+) {
+    for (animal_entity, action, transform, action_range) in animals.iter() {
+        let (other_entity, chance, route) = match *action {
+            Action::Attacking { enemy: other } | Action::Grazing { plant: other } => (
+                other,
+                config.disease.predation_transmission_chance,
+                TransmissionRoute::Predation,
+            ),
+            Action::Eating { food } => (
+                food,
+                config.disease.carcass_transmission_chance,
+                TransmissionRoute::CarcassEating,
+            ),
+            _ => continue,
+        };
+
+        // NOTE: entity could have already disappeared, just ignore it
+        let Ok(other_transform) = transforms.get(other_entity) else {
+            continue;
+        };
+        if tile_layout.get_distance(
+            transform.translation.truncate(),
+            other_transform.translation.truncate(),
+        ) >= action_range.gene.phenotype()
+        {
+            continue;
+        }
+
+        let animal_infection = infections.get(animal_entity).ok();
+        let other_infection = infections.get(other_entity).ok();
+
+        let other_infects_animal = match route {
+            TransmissionRoute::CarcassEating => other_infection.is_some(),
+            _ => other_infection.is_some_and(Infection::is_infectious),
+        };
+        if animal_infection.is_none() && other_infects_animal && rand::random::<f32>() < chance {
+            event_writer.send(InfectionTransmissionEvent {
+                entity: animal_entity,
+                route,
+            });
+        }
+
+        if route == TransmissionRoute::Predation
+            && other_infection.is_none()
+            && animal_infection.is_some_and(Infection::is_infectious)
+            && rand::random::<f32>() < chance
+        {
+            event_writer.send(InfectionTransmissionEvent {
+                entity: other_entity,
+                route,
+            });
+        }
+    }
+}
+
+fn infect(
+    mut commands: Commands,
+    mut event_reader: EventReader<InfectionTransmissionEvent>,
+    organisms: Query<
+        &DiseaseResistance,
+        (
+            Or<(With<AnimalMarker>, With<PlantMarker>)>,
+            Without<Infection>,
+        ),
+    >,
+    config: Res<SimulationConfig>,
+) {
+    let mut infected = HashSet::new();
+
+    for event in event_reader.read() {
+        let Ok(resistance) = organisms.get(event.entity) else {
+            continue;
+        };
+        if infected.contains(&event.entity) || rand::random::<f32>() < resistance.gene.phenotype() {
+            continue;
+        }
+
+        commands
+            .entity(event.entity)
+            .try_insert(Infection::new(&config));
+        infected.insert(event.entity);
+    }
+}
+
+/// Incubation turns into sickness, which drains energy and ends with either death or recovery
+fn progress_infections(
+    mut commands: Commands,
+    mut organisms: Query<
+        (
+            Entity,
+            &mut Infection,
+            &mut Health,
+            &mut EnergyData,
+            &DiseaseResistance,
+        ),
+        Or<(With<AnimalMarker>, With<PlantMarker>)>,
+    >,
+    config: Res<SimulationConfig>,
+) {
+    for (entity, mut infection, mut health, mut energy_data, resistance) in organisms.iter_mut() {
+        if infection.stage == InfectionStage::Sick {
+            let energy_left_to_drain =
+                energy_data.consume_from_active_energy(config.disease.energy_drain);
+            if energy_left_to_drain > 0.0 {
+                health.take_damage(energy_left_to_drain, DamageSource::Disease);
+            }
+        }
+
+        infection.time_units_left = infection.time_units_left.saturating_sub(1);
+        if infection.time_units_left > 0 {
+            continue;
+        }
+
+        match infection.stage {
+            InfectionStage::Incubating => {
+                infection.stage = InfectionStage::Sick;
+                infection.time_units_left = config.disease.infection_duration;
+            }
+            InfectionStage::Sick => {
+                let mortality = config.disease.mortality * (1.0 - resistance.gene.phenotype());
+                if rand::random::<f32>() < mortality {
+                    let hp = health.hp.max(0.0);
+                    health.take_damage(hp, DamageSource::Disease);
+                } else {
+                    commands.entity(entity).remove::<Infection>();
+                }
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use rand::{rngs::ThreadRng, thread_rng, Rng};
 use std::cell::RefCell;
 
+use super::Sex;
 use crate::bella::config::{FloatGeneConfig, IntGeneConfig};
 
 thread_local! {
@@ -14,6 +15,7 @@ impl Plugin for GenePlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<FloatGene>()
             .register_type::<IntGene>()
+            .register_type::<SexLimitedFloatGene>()
             .register_type::<Gene>()
             .register_type::<Allele>()
             .register_type::<AlleleType>();
@@ -61,8 +63,50 @@ impl From<FloatGeneConfig> for FloatGene {
     fn from(value: FloatGeneConfig) -> Self {
         assert!(value.multiplier > 0.0);
         assert!(value.offset >= 0.0);
+        assert!((0.0..=1.0).contains(&value.starting_expression));
+        assert!((0.0..=1.0).contains(&value.mutation_rate));
 
-        Self::new(Gene::new(0.5), value.multiplier, value.offset)
+        Self::new(
+            Gene::new(value.starting_expression, value.mutation_rate),
+            value.multiplier,
+            value.offset,
+        )
+    }
+}
+
+/// Gene carried by both sexes, but only the variant for the sex of the organism is expressed (e.g. males being stronger).
+/// Both variants are inherited, so a father can pass the female variant to his daughters.
+#[derive(Reflect, Debug, Clone)]
+pub struct SexLimitedFloatGene {
+    pub female_gene: FloatGene,
+    pub male_gene: FloatGene,
+    phenotype: f32,
+}
+
+impl SexLimitedFloatGene {
+    pub fn new(female_gene: FloatGene, male_gene: FloatGene, sex: Sex) -> Self {
+        let phenotype = match sex {
+            Sex::Female => female_gene.phenotype(),
+            Sex::Male => male_gene.phenotype(),
+        };
+
+        Self {
+            female_gene,
+            male_gene,
+            phenotype,
+        }
+    }
+
+    pub fn phenotype(&self) -> f32 {
+        self.phenotype
+    }
+
+    pub fn mixed_with(&self, other: &Self, sex: Sex) -> Self {
+        Self::new(
+            self.female_gene.mixed_with(&other.female_gene),
+            self.male_gene.mixed_with(&other.male_gene),
+            sex,
+        )
     }
 }
 
@@ -113,7 +157,13 @@ impl IntGene {
 
 impl From<IntGeneConfig> for IntGene {
     fn from(value: IntGeneConfig) -> Self {
-        IntGene::new(Gene::new(0.5), value.min_value, value.max_value)
+        assert!((0.0..=1.0).contains(&value.mutation_rate));
+
+        IntGene::new(
+            Gene::new(0.5, value.mutation_rate),
+            value.min_value,
+            value.max_value,
+        )
     }
 }
 
@@ -121,14 +171,17 @@ impl From<IntGeneConfig> for IntGene {
 pub struct Gene {
     pub allele1: Allele,
     pub allele2: Allele,
+    /// chance that an allele mutates when it's passed to offspring
+    pub mutation_rate: f32,
 }
 
 impl Gene {
-    pub fn new(gene_starting_value_percentage: f32) -> Self {
+    /// NOTE: founders mutate too, so the starting population isn't made of clones
+    pub fn new(gene_starting_value_percentage: f32, mutation_rate: f32) -> Self {
         let byte_value = (gene_starting_value_percentage * 255.0) as u8;
 
         // TODO: right now it's always 1 Dominant and 1 Recessive. Is that ok? It honestly might be
-        Self {
+        let mut gene = Self {
             allele1: Allele {
                 allele_type: AlleleType::Dominant,
                 bytes: vec![byte_value],
@@ -137,7 +190,12 @@ impl Gene {
                 allele_type: AlleleType::Recessive,
                 bytes: vec![byte_value],
             },
-        }
+            mutation_rate,
+        };
+        gene.allele1.mutate_with_chance(mutation_rate);
+        gene.allele2.mutate_with_chance(mutation_rate);
+
+        gene
     }
 
     pub fn expression_level(&self) -> f32 {
@@ -152,8 +210,9 @@ impl Gene {
         }
     }
 
+    /// Takes one random allele from each parent, each of them can mutate on the way
     pub fn cross_with(&self, other: &Gene) -> Self {
-        RNG.with(|rng| {
+        let (allele1, allele2) = RNG.with(|rng| {
             let mut rng = rng.borrow_mut();
 
             let allele1 = if rng.gen_bool(0.5) {
@@ -168,8 +227,18 @@ impl Gene {
                 other.allele2.clone()
             };
 
-            Gene { allele1, allele2 }
-        })
+            (allele1, allele2)
+        });
+
+        let mut gene = Gene {
+            allele1,
+            allele2,
+            mutation_rate: self.mutation_rate,
+        };
+        gene.allele1.mutate_with_chance(self.mutation_rate);
+        gene.allele2.mutate_with_chance(self.mutation_rate);
+
+        gene
     }
 
     fn alleles_have_different_types(&self) -> bool {
@@ -210,11 +279,20 @@ impl Allele {
         average / u8::MAX as f32
     }
 
+    /// Flips one random bit, so most mutations are small and a few are big
     pub fn mutate(&mut self) {
-        // TODO: add rng
-        for byte in self.bytes.iter_mut() {
-            let index = 7;
-            *byte ^= 1 << index;
+        RNG.with(|rng| {
+            let mut rng = rng.borrow_mut();
+
+            let byte_index = rng.gen_range(0..self.bytes.len());
+            let bit_index = rng.gen_range(0..u8::BITS);
+            self.bytes[byte_index] ^= 1 << bit_index;
+        });
+    }
+
+    pub fn mutate_with_chance(&mut self, chance: f32) {
+        if RNG.with(|rng| rng.borrow_mut().gen::<f32>()) < chance {
+            self.mutate();
         }
     }
 
@@ -237,3 +315,42 @@ pub enum AlleleType {
     Dominant,
     Recessive,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent_with_mutation_rate(mutation_rate: f32) -> FloatGene {
+        let gene = Gene {
+            allele1: Allele::new(AlleleType::Dominant),
+            allele2: Allele::new(AlleleType::Recessive),
+            mutation_rate,
+        };
+
+        FloatGene::new(gene, 1.0, 0.0)
+    }
+
+    fn offspring_phenotypes(parent: &FloatGene) -> Vec<f32> {
+        (0..100)
+            .map(|_| parent.mixed_with(parent).phenotype())
+            .collect()
+    }
+
+    #[test]
+    fn offspring_of_identical_parents_are_identical_without_mutations() {
+        let parent = parent_with_mutation_rate(0.0);
+
+        assert!(offspring_phenotypes(&parent)
+            .iter()
+            .all(|&phenotype| phenotype == parent.phenotype()));
+    }
+
+    #[test]
+    fn offspring_phenotypes_vary_with_mutations() {
+        let parent = parent_with_mutation_rate(0.5);
+
+        assert!(offspring_phenotypes(&parent)
+            .iter()
+            .any(|&phenotype| phenotype != parent.phenotype()));
+    }
+}
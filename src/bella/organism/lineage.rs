@@ -0,0 +1,109 @@
+use super::{animal::AnimalMarker, plant::PlantMarker};
+use crate::bella::{restart::SimulationState, time::SimulationTime};
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+pub struct LineagePlugin;
+
+impl Plugin for LineagePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Lineage>()
+            .init_resource::<OrganismIdGenerator>()
+            .add_systems(
+                OnEnter(SimulationState::PreSimulation),
+                (reset_organism_id_generator, assign_lineage_to_founders).chain(),
+            );
+    }
+}
+
+/// Permanent identity of an organism and its place in the pedigree.
+/// Unlike `Entity`, `id` is never reused, so it can be used to link organisms in the data output.
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct Lineage {
+    pub id: u64,
+    /// `None` for organisms from the starting population
+    pub mother: Option<u64>,
+    pub father: Option<u64>,
+    /// `0` for the starting population, otherwise one more than the older generation of parents
+    pub generation: u32,
+    pub born_on_day: u64,
+    pub born_at_time_unit: u64,
+}
+
+impl Lineage {
+    pub fn founder(id_generator: &mut OrganismIdGenerator, time: &SimulationTime) -> Self {
+        Self {
+            id: id_generator.next_id(),
+            mother: None,
+            father: None,
+            generation: 0,
+            born_on_day: time.days_passed(),
+            born_at_time_unit: time.time_units_this_day(),
+        }
+    }
+
+    pub fn offspring(
+        id_generator: &mut OrganismIdGenerator,
+        time: &SimulationTime,
+        mother: &Lineage,
+        father: &Lineage,
+    ) -> Self {
+        Self {
+            id: id_generator.next_id(),
+            mother: Some(mother.id),
+            father: Some(father.id),
+            generation: mother.generation.max(father.generation) + 1,
+            born_on_day: time.days_passed(),
+            born_at_time_unit: time.time_units_this_day(),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct OrganismIdGenerator {
+    next_id: u64,
+}
+
+impl OrganismIdGenerator {
+    pub fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        id
+    }
+}
+
+/// Resources needed to give lineage to organisms born during the simulation
+#[derive(SystemParam)]
+pub struct OffspringLineage<'w> {
+    id_generator: ResMut<'w, OrganismIdGenerator>,
+    time: Res<'w, SimulationTime>,
+}
+
+impl OffspringLineage<'_> {
+    pub fn new_lineage(&mut self, mother: &Lineage, father: &Lineage) -> Lineage {
+        Lineage::offspring(&mut self.id_generator, &self.time, mother, father)
+    }
+}
+
+fn reset_organism_id_generator(mut id_generator: ResMut<OrganismIdGenerator>) {
+    *id_generator = OrganismIdGenerator::default();
+}
+
+fn assign_lineage_to_founders(
+    mut commands: Commands,
+    organisms: Query<
+        Entity,
+        (
+            Or<(With<AnimalMarker>, With<PlantMarker>)>,
+            Without<Lineage>,
+        ),
+    >,
+    mut id_generator: ResMut<OrganismIdGenerator>,
+    time: Res<SimulationTime>,
+) {
+    for entity in organisms.iter() {
+        commands
+            .entity(entity)
+            .insert(Lineage::founder(&mut id_generator, &time));
+    }
+}
@@ -1,22 +1,29 @@
+pub mod seed;
+
 use std::cell::RefCell;
 
 use super::{
-    gene::FloatGene, Age, BasicBundle, OrganismBundle, OrganismEnergyEfficiency, SexualMaturity,
+    disease::DiseaseResistance, gene::FloatGene, lineage::Lineage, species::SpeciesId, Age,
+    BasicBundle, OrganismBundle, OrganismEnergyEfficiency, Senescence, Sex, SexualMaturity,
+    ThermalTolerance,
 };
 use crate::bella::{
     config::SimulationConfig,
     environment::Sun,
-    organism::{EnergyData, Health},
+    organism::{DamageSource, EnergyData, Health},
     restart::SimulationState,
     terrain::{
+        spatial_query::SpatialQuery,
+        thermal_conductor::TileTemperature,
         tile::{Tile, TileLayout},
-        BiomeType, Humidity, Nutrients, ObjectsInTile,
+        BiomeType, Humidity, Nutrients, ObjectKind, ObjectsInTile,
     },
     time::TimeUnitPassedEvent,
     ui_facade::choose_entity_observer,
 };
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use rand::{rngs::ThreadRng, thread_rng, Rng};
+use seed::{Seed, SeedDispersal, SeedPlugin};
 
 thread_local! {
     static RNG: RefCell<ThreadRng> = RefCell::new(thread_rng());
@@ -28,6 +35,9 @@ impl Plugin for PlantPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<PlantEnergyEfficiency>()
             .register_type::<PollinationRange>()
+            .register_type::<PlantHeight>()
+            .register_type::<ChemicalDefense>()
+            .add_plugins(SeedPlugin)
             .add_event::<ReproducePlantsEvent>()
             .add_systems(OnEnter(SimulationState::LoadAssets), prepare_plant_assets)
             .add_systems(OnEnter(SimulationState::OrganismGeneration), spawn_plants)
@@ -62,6 +72,9 @@ pub struct PlantBundle {
     matter_marker: PlantMatterMarker,
     plant_energy_efficiency: PlantEnergyEfficiency,
     pollination_range: PollinationRange,
+    seed_dispersal: SeedDispersal,
+    plant_height: PlantHeight,
+    chemical_defense: ChemicalDefense,
 }
 
 #[derive(Event)]
@@ -73,6 +86,7 @@ pub struct ReproducePlantsEvent {
 #[derive(Resource)]
 pub struct PlantAssets {
     alive: Handle<StandardMaterial>,
+    seed: Handle<StandardMaterial>,
 }
 
 #[derive(Component, Reflect, Debug, Clone)]
@@ -95,7 +109,7 @@ impl PlantEnergyEfficiency {
 
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct PollinationRange {
-    gene: FloatGene,
+    pub gene: FloatGene,
 }
 
 impl PollinationRange {
@@ -104,9 +118,55 @@ impl PollinationRange {
     }
 }
 
+/// Taller plants get the light first and shade the shorter ones, but height costs energy to maintain
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct PlantHeight {
+    pub gene: FloatGene,
+}
+
+impl PlantHeight {
+    pub fn new(gene: impl Into<FloatGene>) -> Self {
+        Self { gene: gene.into() }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(self.gene.mixed_with(&other.gene))
+    }
+
+    /// Plants grow taller together with their size
+    pub fn get_height(&self, energy_data: &EnergyData) -> f32 {
+        self.gene.phenotype() * energy_data.get_size()
+    }
+}
+
+/// Toxins that lower the energy herbivores get from grazing the plant and poison them,
+/// unless they are tolerant to them. Producing toxins costs energy.
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct ChemicalDefense {
+    pub toxicity_gene: FloatGene,
+}
+
+impl ChemicalDefense {
+    pub fn new(toxicity_gene: impl Into<FloatGene>) -> Self {
+        Self {
+            toxicity_gene: toxicity_gene.into(),
+        }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(self.toxicity_gene.mixed_with(&other.toxicity_gene))
+    }
+
+    /// Part of the toxicity that isn't neutralized by the eater's tolerance, from `0.0` to `1.0`
+    pub fn get_effective_toxicity(&self, toxin_tolerance: f32) -> f32 {
+        (self.toxicity_gene.phenotype() - toxin_tolerance).clamp(0.0, 1.0)
+    }
+}
+
 fn prepare_plant_assets(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
     let plant_assets = PlantAssets {
         alive: materials.add(Color::srgb(0.0, 1.0, 0.0)),
+        seed: materials.add(Color::srgb(0.5, 0.3, 0.1)),
     };
 
     commands.insert_resource(plant_assets);
@@ -135,12 +195,17 @@ fn spawn_plants(
         let plant_count = config.plant.group_size_dist.sample();
 
         for _ in 0..plant_count {
-            let health = Health::new(config.organism.max_health_gene_config.into());
+            let health = Health::new(
+                config.organism.max_health_gene_config.into(),
+                config.organism.regeneration_gene_config.into(),
+            );
             let starting_age = config.organism.starting_age_dist.sample();
             let age = Age::new(starting_age, config.organism.age_penalty_gene_config.into());
+            let senescence = Senescence::from_config(&config.organism);
             let sexual_maturity = SexualMaturity::new(
                 config.plant.maturity_age_gene_config.into(),
                 config.plant.reproduction_cooldown_gene_config.into(),
+                config.organism.max_reproduction_count_gene_config.into(),
                 starting_age,
             );
             let energy_data = EnergyData::new(
@@ -153,8 +218,15 @@ fn spawn_plants(
                     .plant
                     .energy_to_survive_per_mass_unit_gene_config
                     .into(),
-                config.organism.reproduction_energy_cost_gene_config.into(),
+                config.plant.reproduction_energy_cost_gene_config.into(),
+            );
+
+            let thermal_tolerance = ThermalTolerance::new(
+                config.organism.optimal_temperature_gene_config.into(),
+                config.organism.temperature_tolerance_gene_config.into(),
             );
+            let disease_resistance =
+                DiseaseResistance::new(config.disease.resistance_gene_config.into());
 
             let plant_energy_efficiency = PlantEnergyEfficiency::new(
                 config
@@ -164,33 +236,45 @@ fn spawn_plants(
             );
             let pollination_range =
                 PollinationRange::new(config.plant.pollination_range_gene_config);
+            let seed_dispersal =
+                SeedDispersal::new(config.plant.seed_dispersal_distance_gene_config);
+            let plant_height = PlantHeight::new(config.plant.height_gene_config);
+            let chemical_defense = ChemicalDefense::new(config.plant.toxicity_gene_config);
 
             let size = energy_data.get_size();
             let position = tile_layout.get_random_position_in_tile(tile);
 
-            let entity = commands
-                .spawn((
-                    BasicBundle {
-                        mesh: Mesh3d(mesh_handle.clone()),
-                        material: MeshMaterial3d(plant_assets.alive.clone()),
-                        transform: Transform::from_translation(position.extend(size / 2.0))
-                            .with_scale(Vec3::splat(size)),
-                    },
-                    PlantBundle {
-                        organism_bundle: OrganismBundle {
-                            health,
-                            age,
-                            sexual_maturity,
-                            energy_data,
-                            organism_energy_efficiency,
-                        },
-                        marker: PlantMarker,
-                        matter_marker: PlantMatterMarker,
-                        plant_energy_efficiency,
-                        pollination_range,
+            let mut entity_commands = commands.spawn((
+                BasicBundle {
+                    mesh: Mesh3d(mesh_handle.clone()),
+                    material: MeshMaterial3d(plant_assets.alive.clone()),
+                    transform: Transform::from_translation(position.extend(size / 2.0))
+                        .with_scale(Vec3::splat(size)),
+                },
+                PlantBundle {
+                    organism_bundle: OrganismBundle {
+                        health,
+                        age,
+                        senescence,
+                        sexual_maturity,
+                        energy_data,
+                        organism_energy_efficiency,
+                        thermal_tolerance,
+                        disease_resistance,
                     },
-                ))
-                .id();
+                    marker: PlantMarker,
+                    matter_marker: PlantMatterMarker,
+                    plant_energy_efficiency,
+                    pollination_range,
+                    seed_dispersal,
+                    plant_height,
+                    chemical_defense,
+                },
+            ));
+            if config.plant.separate_sexes {
+                entity_commands.insert(Sex::random());
+            }
+            let entity = entity_commands.id();
 
             objects_in_tile.add_plant_entity(entity);
             choose_entity_observer.watch_entity(entity);
@@ -206,14 +290,86 @@ fn despawn_plants(mut commands: Commands, plants: Query<Entity, With<PlantMarker
     }
 }
 
+/// Light of a tile is absorbed by canopy layers from the tallest plants to the shortest, following the Beer-Lambert law.
+/// Plants of the same height share the light of their layer proportionally to their leaf area.
+fn distribute_light(
+    plants: &mut [(Entity, f32, f32)],
+    light_for_tile: f32,
+    extinction_coefficient: f32,
+    absorbed_light: &mut HashMap<Entity, f32>,
+) {
+    plants.sort_by(|(_, height1, _), (_, height2, _)| height2.total_cmp(height1));
+
+    let mut light_left = light_for_tile;
+    for layer in plants.chunk_by(|(_, height1, _), (_, height2, _)| height1 == height2) {
+        let layer_leaf_area: f32 = layer.iter().map(|(_, _, leaf_area)| leaf_area).sum();
+        let layer_light = light_left * (1.0 - (-extinction_coefficient * layer_leaf_area).exp());
+        for &(entity, _, leaf_area) in layer {
+            absorbed_light.insert(
+                entity,
+                layer_light * leaf_area / layer_leaf_area.max(f32::EPSILON),
+            );
+        }
+        light_left -= layer_light;
+    }
+}
+
 fn produce_energy_from_solar(
-    mut query: Query<(&mut EnergyData, &PlantEnergyEfficiency, &Transform), With<PlantMarker>>,
+    mut query: Query<
+        (
+            Entity,
+            &mut EnergyData,
+            &mut Health,
+            &PlantEnergyEfficiency,
+            &PlantHeight,
+            &ChemicalDefense,
+            &ThermalTolerance,
+            &Age,
+            &Senescence,
+            &Transform,
+        ),
+        With<PlantMarker>,
+    >,
     mut nutrients_query: Query<&mut Nutrients>,
     humidity_query: Query<&Humidity>,
+    biome_and_temperature_query: Query<(&BiomeType, &TileTemperature)>,
     tile_layout: Res<TileLayout>,
     sun: Res<Sun>,
+    config: Res<SimulationConfig>,
 ) {
-    for (mut energy_data, energy_efficiency, transform) in query.iter_mut() {
+    // NOTE: plant's (height, leaf area) for every tile
+    let mut plants_in_tiles: HashMap<Entity, Vec<(Entity, f32, f32)>> = HashMap::new();
+    for (entity, energy_data, _, _, plant_height, _, _, _, _, transform) in query.iter() {
+        let size = energy_data.get_size();
+        plants_in_tiles
+            .entry(tile_layout.get_tile_entity_for_transform(transform))
+            .or_default()
+            .push((entity, plant_height.get_height(energy_data), size * size));
+    }
+
+    let mut absorbed_light = HashMap::new();
+    for plants in plants_in_tiles.values_mut() {
+        distribute_light(
+            plants,
+            sun.get_light_for_tile(),
+            config.plant.light_extinction_coefficient,
+            &mut absorbed_light,
+        );
+    }
+
+    for (
+        entity,
+        mut energy_data,
+        mut health,
+        energy_efficiency,
+        plant_height,
+        chemical_defense,
+        thermal_tolerance,
+        age,
+        senescence,
+        transform,
+    ) in query.iter_mut()
+    {
         let tile_entity = tile_layout.get_tile_entity_for_transform(transform);
 
         let mut tile_nutrients = nutrients_query
@@ -227,76 +383,104 @@ fn produce_energy_from_solar(
             .expect("Failed to get tile's humidity from query!");
         let humidity_value = tile_humidity.value;
 
-        let produced_energy = sun.get_energy_for_plant()
+        let (tile_biome, tile_temperature) = biome_and_temperature_query
+            .get(tile_entity)
+            .expect("Failed to get tile's biome and temperature from query!");
+        let plant_suitability = config.terrain.biomes.get(tile_biome).plant_suitability;
+
+        let thermal_multiplier = thermal_tolerance.get_production_multiplier(
+            tile_temperature.value,
+            config.organism.min_temperature_tolerance,
+        );
+
+        let produced_energy = absorbed_light[&entity]
             * energy_efficiency.production_from_solar_gene.phenotype()
             * nutrients_value
-            * humidity_value;
+            * humidity_value
+            * plant_suitability
+            * thermal_multiplier
+            * senescence.photosynthesis.get_multiplier(age);
 
         energy_data.store_energy(produced_energy);
+
+        let maintenance_cost = plant_height.get_height(&energy_data)
+            * config.plant.height_maintenance_cost
+            + chemical_defense.toxicity_gene.phenotype() * config.plant.toxin_production_cost;
+        let energy_left_to_consume = energy_data.consume_from_active_energy(maintenance_cost);
+        if energy_left_to_consume > 0.0 {
+            health.take_damage(energy_left_to_consume, DamageSource::Starvation);
+        }
     }
 }
 
 fn send_reproduce_events_if_possible(
     mut reproduction_ew: EventWriter<ReproducePlantsEvent>,
-    objects_in_tile_query: Query<&ObjectsInTile>,
-    tile_layout: Res<TileLayout>,
+    spatial_query: SpatialQuery,
     mut plants_query: Query<
-        (Entity, &Transform, &PollinationRange, &mut SexualMaturity),
+        (
+            Entity,
+            &Transform,
+            &PollinationRange,
+            &mut SexualMaturity,
+            Option<&Sex>,
+            Option<&SpeciesId>,
+        ),
         With<PlantMarker>,
     >,
 ) {
-    let mut plants_that_will_reproduce = Vec::new();
+    let mut pollinated_pairs = Vec::new();
 
-    for (plant_entity, plant_transform, pollination_range, sexual_maturity) in plants_query.iter() {
+    for (plant_entity, plant_transform, pollination_range, sexual_maturity, sex, species_id) in
+        plants_query.iter()
+    {
         if !sexual_maturity.is_ready_to_reproduce() {
             continue;
         }
 
-        let chosen_partner_entity = tile_layout
-            .get_tile_entities_in_range(
-                plant_transform.translation.truncate(),
-                pollination_range.gene.phenotype(),
-            )
-            .iter()
-            .flat_map(|tile_entity| {
-                objects_in_tile_query
-                    .get(*tile_entity)
-                    .expect("Failed to get tile")
-                    .plants
-                    .clone()
-            })
-            .filter_map(|entity| {
-                let (entity, transform, _, sexual_maturity) = plants_query.get(entity).expect(
-                    "Failed to get plant entity despite that entity being in ObjectsInTile",
-                );
-                if sexual_maturity.is_ready_to_reproduce() {
-                    let distance = plant_transform.translation.distance(transform.translation);
-
-                    Some((entity, distance))
-                } else {
-                    None
-                }
-            })
-            .max_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
-            .map(|(entity, _)| entity);
+        let chosen_partner_entity = spatial_query.find_nearest(
+            plant_transform.translation.truncate(),
+            pollination_range.gene.phenotype(),
+            &[ObjectKind::Plant],
+            |other_entity| {
+                other_entity != plant_entity
+                    && plants_query.get(other_entity).is_ok_and(
+                        |(_, _, _, sexual_maturity, other_sex, other_species_id)| {
+                            sexual_maturity.is_ready_to_reproduce()
+                                && Sex::can_mate(sex, other_sex)
+                                && species_id == other_species_id
+                        },
+                    )
+            },
+        );
 
         if let Some(partner_entity) = chosen_partner_entity {
-            reproduction_ew.send(ReproducePlantsEvent {
-                parent1: plant_entity,
-                parent2: partner_entity,
-            });
-            plants_that_will_reproduce.push(plant_entity);
-            plants_that_will_reproduce.push(partner_entity);
+            pollinated_pairs.push((plant_entity, partner_entity));
         };
     }
 
-    plants_that_will_reproduce
-        .into_iter()
-        .for_each(|plant_entity| {
-            if let Ok((_, _, _, mut sexual_maturity)) = plants_query.get_mut(plant_entity) {
-                sexual_maturity.reset_reproduction_cooldown();
-            }
+    for (plant_entity, partner_entity) in pollinated_pairs {
+        // NOTE: plant could have already been pollinated as someone else's partner
+        let both_ready = [plant_entity, partner_entity].iter().all(|&entity| {
+            plants_query
+                .get(entity)
+                .is_ok_and(|(_, _, _, sexual_maturity, _, _)| {
+                    sexual_maturity.is_ready_to_reproduce()
+                })
         });
+        if !both_ready {
+            continue;
+        }
+
+        reproduction_ew.send(ReproducePlantsEvent {
+            parent1: plant_entity,
+            parent2: partner_entity,
+        });
+        for entity in [plant_entity, partner_entity] {
+            if let Ok((_, _, _, mut sexual_maturity, _, _)) = plants_query.get_mut(entity) {
+                sexual_maturity.register_reproduction();
+            }
+        }
+    }
 }
 
 fn reproduce(
@@ -305,76 +489,83 @@ fn reproduce(
     plant_assets: Res<PlantAssets>,
     tile_layout: Res<TileLayout>,
     config: Res<SimulationConfig>,
-    query: Query<(
+    mut query: Query<(
         &Mesh3d,
         &Transform,
         &Health,
         &SexualMaturity,
-        &EnergyData,
+        &mut EnergyData,
         &OrganismEnergyEfficiency,
         &PlantEnergyEfficiency,
         &PollinationRange,
         &Age,
+        &Senescence,
+        &ThermalTolerance,
+        &DiseaseResistance,
+        (
+            &SpeciesId,
+            &Lineage,
+            &SeedDispersal,
+            &PlantHeight,
+            &ChemicalDefense,
+        ),
+        Option<&Sex>,
     )>,
-    mut tiles: Query<(&BiomeType, &mut ObjectsInTile)>,
+    mut tiles: Query<&mut ObjectsInTile>,
 ) {
-    let mut choose_entity_observer = Observer::new(choose_entity_observer);
-
     for event in event_reader.read() {
-        let Ok((
-            mesh1,
-            transform1,
-            health1,
-            sexual_maturity1,
-            energy_data1,
-            organism_energy_efficiency1,
-            plant_energy_efficiency1,
-            pollination_range1,
-            age1,
-        )) = query.get(event.parent1)
-        else {
-            continue;
-        };
-        let Ok((
-            _,
-            transform2,
-            health2,
-            sexual_maturity2,
-            energy_data2,
-            organism_energy_efficiency2,
-            plant_energy_efficiency2,
-            pollination_range2,
-            age2,
-        )) = query.get(event.parent2)
+        let Ok(
+            [(
+                mesh1,
+                transform1,
+                health1,
+                sexual_maturity1,
+                mut energy_data1,
+                organism_energy_efficiency1,
+                plant_energy_efficiency1,
+                pollination_range1,
+                age1,
+                senescence1,
+                thermal_tolerance1,
+                disease_resistance1,
+                (species_id1, lineage1, seed_dispersal1, plant_height1, chemical_defense1),
+                sex1,
+            ), (
+                _,
+                transform2,
+                health2,
+                sexual_maturity2,
+                mut energy_data2,
+                organism_energy_efficiency2,
+                plant_energy_efficiency2,
+                pollination_range2,
+                age2,
+                senescence2,
+                thermal_tolerance2,
+                disease_resistance2,
+                (_, lineage2, seed_dispersal2, plant_height2, chemical_defense2),
+                _,
+            )],
+        ) = query.get_many_mut([event.parent1, event.parent2])
         else {
             continue;
         };
 
         // crossing parent organism genes
-        let health = Health::new(health1.max_hp_gene.mixed_with(&health2.max_hp_gene));
+        let health = health1.mixed_with(health2);
         let starting_age = 0;
         let age = Age::new(
             starting_age,
             age1.age_penalty_gene.mixed_with(&age2.age_penalty_gene),
         );
-        let sexual_maturity = SexualMaturity::new(
-            sexual_maturity1
-                .maturity_age_gene
-                .mixed_with(&sexual_maturity2.maturity_age_gene),
-            sexual_maturity1
-                .reproduction_cooldown_gene
-                .mixed_with(&sexual_maturity2.reproduction_cooldown_gene),
-            starting_age,
-        );
-        let energy_data = EnergyData::new(
-            energy_data1
-                .max_active_energy_gene
-                .mixed_with(&energy_data2.max_active_energy_gene),
-            energy_data1
-                .energy_per_mass_unit_gene
-                .mixed_with(&energy_data2.energy_per_mass_unit_gene),
-            config.organism.starting_mass_dist.sample(),
-        );
+        let senescence = senescence1.mixed_with(senescence2);
+        let sexual_maturity = sexual_maturity1.mixed_with(sexual_maturity2, starting_age);
+        let max_active_energy_gene = energy_data1
+            .max_active_energy_gene
+            .mixed_with(&energy_data2.max_active_energy_gene);
+        let energy_per_mass_unit_gene = energy_data1
+            .energy_per_mass_unit_gene
+            .mixed_with(&energy_data2.energy_per_mass_unit_gene);
         let organism_energy_efficiency = OrganismEnergyEfficiency::new(
             organism_energy_efficiency1
                 .energy_consumption_to_survive_per_mass_unit_gene
@@ -385,6 +576,8 @@ fn reproduce(
                 .reproduction_energy_cost_gene
                 .mixed_with(&organism_energy_efficiency2.reproduction_energy_cost_gene),
         );
+        let thermal_tolerance = thermal_tolerance1.mixed_with(thermal_tolerance2);
+        let disease_resistance = disease_resistance1.mixed_with(disease_resistance2);
 
         // crossing parent plant genes
         let plant_energy_efficiency = PlantEnergyEfficiency::new(
@@ -397,68 +590,128 @@ fn reproduce(
         );
         let pollination_range =
             PollinationRange::new(pollination_range1.gene.mixed_with(&pollination_range2.gene));
+        let seed_dispersal = seed_dispersal1.mixed_with(seed_dispersal2);
+        let plant_height = plant_height1.mixed_with(plant_height2);
+        let chemical_defense = chemical_defense1.mixed_with(chemical_defense2);
 
         // other setup
-        let point = RNG.with(|rng| {
-            let mut rng = rng.borrow_mut();
-            if rng.gen_bool(0.5) {
-                transform1.translation.truncate()
-            } else {
-                transform2.translation.truncate()
-            }
-        });
+        // NOTE: parents are always of opposite sexes, plants without sexes "give birth" at a random parent, which counts as the mother
+        let is_first_parent_mother = match sex1 {
+            Some(Sex::Female) => true,
+            Some(Sex::Male) => false,
+            None => RNG.with(|rng| rng.borrow_mut().gen_bool(0.5)),
+        };
+        let (
+            point,
+            mother_lineage,
+            father_lineage,
+            mother_age,
+            mother_senescence,
+            mother_dispersal,
+            mother_energy_data,
+            mother_organism_energy_efficiency,
+        ) = if is_first_parent_mother {
+            (
+                transform1.translation.truncate(),
+                lineage1,
+                lineage2,
+                age1,
+                senescence1,
+                seed_dispersal1,
+                &mut energy_data1,
+                organism_energy_efficiency1,
+            )
+        } else {
+            (
+                transform2.translation.truncate(),
+                lineage2,
+                lineage1,
+                age2,
+                senescence2,
+                seed_dispersal2,
+                &mut energy_data2,
+                organism_energy_efficiency2,
+            )
+        };
 
-        let (new_plant_position, mut objects_in_tile) = loop {
-            let new_plant_position = tile_layout.get_random_position_in_ring(
-                point,
-                config.organism.offspring_spawn_range,
-                config.organism.offspring_spawn_range / 2.0,
-            );
+        // NOTE: old mothers are less likely to conceive
+        let fertility = mother_senescence.fertility.get_multiplier(mother_age);
+        if RNG.with(|rng| rng.borrow_mut().gen::<f32>()) >= fertility {
+            continue;
+        }
 
-            let entity_of_tile_under = tile_layout.get_tile_entity_for_position(new_plant_position);
+        // NOTE: seeds are carried by the mother's genes, seeds landing outside of the map are lost
+        let dispersal_distance = mother_dispersal.distance_gene.phenotype().max(f32::EPSILON);
+        let Some(seed_position) = tile_layout.try_get_random_position_in_ring(
+            point,
+            dispersal_distance,
+            dispersal_distance / 2.0,
+        ) else {
+            continue;
+        };
+        let Some(tile_entity) = tile_layout.try_get_tile_entity_for_position(seed_position) else {
+            continue;
+        };
 
-            let (biome_under_new_plant, objects_in_tile) = tiles
-                .get_mut(entity_of_tile_under)
-                .expect("Failed to get tile components of tile for new plant position");
+        // NOTE: mother pays for the seed, even if it never germinates
+        let invested_energy = mother_organism_energy_efficiency
+            .reproduction_energy_cost_gene
+            .phenotype();
+        if mother_energy_data
+            .try_to_consume_energy(invested_energy)
+            .is_err()
+        {
+            continue;
+        }
+        let energy_data = EnergyData::from_invested_energy(
+            max_active_energy_gene,
+            energy_per_mass_unit_gene,
+            invested_energy,
+            config.plant.seed_mass_energy_ratio,
+        );
 
-            if biome_under_new_plant.plants_can_live_here() {
-                break (new_plant_position, objects_in_tile);
-            }
+        let mut objects_in_tile = tiles
+            .get_mut(tile_entity)
+            .expect("Failed to get tile components of tile under new seed");
+
+        // NOTE: seed is drawn smaller than the plant that grows from it
+        let seed_size = energy_data.get_size() / 4.0;
+        let seed = Seed {
+            health,
+            age,
+            senescence,
+            sexual_maturity,
+            energy_data,
+            organism_energy_efficiency,
+            thermal_tolerance,
+            disease_resistance,
+            plant_energy_efficiency,
+            pollination_range,
+            seed_dispersal,
+            plant_height,
+            chemical_defense,
+            // NOTE: separate sexes are inherited, but the sex itself is random
+            separate_sexes: sex1.is_some(),
+            species_id: *species_id1,
+            mother_lineage: mother_lineage.clone(),
+            father_lineage: father_lineage.clone(),
+            dormancy_time_units_left: config.plant.seed_dormancy_time,
+            viability_time_units_left: config.plant.seed_viability_time,
         };
 
-        let new_size = energy_data.get_size();
-        let mut transform =
-            Transform::from_translation(new_plant_position.extend(energy_data.get_size() / 2.0))
-                .with_scale(Vec3::splat(new_size));
-        transform.translation.z = new_size / 2.0;
-
         let entity = commands
             .spawn((
                 BasicBundle {
                     mesh: mesh1.clone(),
-                    material: MeshMaterial3d(plant_assets.alive.clone()),
-                    transform,
-                },
-                PlantBundle {
-                    organism_bundle: OrganismBundle {
-                        health,
-                        age,
-                        sexual_maturity,
-                        energy_data,
-                        organism_energy_efficiency,
-                    },
-                    marker: PlantMarker,
-                    matter_marker: PlantMatterMarker,
-                    plant_energy_efficiency,
-                    pollination_range,
+                    material: MeshMaterial3d(plant_assets.seed.clone()),
+                    transform: Transform::from_translation(seed_position.extend(seed_size / 2.0))
+                        .with_scale(Vec3::splat(seed_size)),
                 },
+                seed,
             ))
             .id();
-        objects_in_tile.add_plant_entity(entity);
-        choose_entity_observer.watch_entity(entity);
+        objects_in_tile.add_seed_entity(entity);
     }
-
-    commands.spawn(choose_entity_observer);
 }
 
 // fn give_plant_energy_from_thermal_conductor_its_on(
@@ -0,0 +1,274 @@
+use super::{
+    ChemicalDefense, PlantAssets, PlantBundle, PlantEnergyEfficiency, PlantHeight, PlantMarker,
+    PlantMatterMarker, PollinationRange,
+};
+use crate::bella::{
+    config::SimulationConfig,
+    organism::{
+        animal::AnimalMarker,
+        disease::DiseaseResistance,
+        gene::FloatGene,
+        lineage::{Lineage, OffspringLineage},
+        species::SpeciesId,
+        Age, BasicBundle, EnergyData, Health, OrganismBundle, OrganismEnergyEfficiency, Senescence,
+        Sex, SexualMaturity, ThermalTolerance,
+    },
+    pause::PauseState,
+    restart::SimulationState,
+    terrain::{tile::TileLayout, BiomeType, Humidity, Nutrients, ObjectsInTile},
+    time::TimeUnitPassedEvent,
+    ui_facade::choose_entity_observer,
+};
+use bevy::prelude::*;
+
+pub struct SeedPlugin;
+
+impl Plugin for SeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Seed>()
+            .register_type::<SeedDispersal>()
+            .register_type::<CarriedBy>()
+            .add_systems(OnExit(SimulationState::Simulation), despawn_seeds)
+            .add_systems(
+                Update,
+                destroy_eaten_seeds
+                    .run_if(in_state(PauseState::Running))
+                    .run_if(in_state(SimulationState::Simulation)),
+            )
+            .add_systems(
+                Update,
+                (attach_seeds_to_animals, carry_seeds, germinate_seeds)
+                    .chain()
+                    .run_if(on_event::<TimeUnitPassedEvent>)
+                    .run_if(in_state(SimulationState::Simulation)),
+            );
+    }
+}
+
+/// How far from the mother plant its seeds land
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct SeedDispersal {
+    pub distance_gene: FloatGene,
+}
+
+impl SeedDispersal {
+    pub fn new(distance_gene: impl Into<FloatGene>) -> Self {
+        Self {
+            distance_gene: distance_gene.into(),
+        }
+    }
+
+    pub fn mixed_with(&self, other: &Self) -> Self {
+        Self::new(self.distance_gene.mixed_with(&other.distance_gene))
+    }
+}
+
+/// Dormant offspring of two plants, it germinates into a new plant once it lands on a suitable tile.
+/// `energy_data.mass` is the mass of the seedling, herbivores eating the seed take it away.
+#[derive(Component, Reflect, Debug)]
+pub struct Seed {
+    pub health: Health,
+    pub age: Age,
+    pub senescence: Senescence,
+    pub sexual_maturity: SexualMaturity,
+    pub energy_data: EnergyData,
+    pub organism_energy_efficiency: OrganismEnergyEfficiency,
+    pub thermal_tolerance: ThermalTolerance,
+    pub disease_resistance: DiseaseResistance,
+    pub plant_energy_efficiency: PlantEnergyEfficiency,
+    pub pollination_range: PollinationRange,
+    pub seed_dispersal: SeedDispersal,
+    pub plant_height: PlantHeight,
+    pub chemical_defense: ChemicalDefense,
+    /// plant growing from this seed gets a random sex
+    pub separate_sexes: bool,
+    pub species_id: SpeciesId,
+    pub mother_lineage: Lineage,
+    pub father_lineage: Lineage,
+    /// seed can't germinate until it reaches `0`
+    pub dormancy_time_units_left: u32,
+    /// seed dies when it reaches `0`
+    pub viability_time_units_left: u32,
+}
+
+/// Seed stuck to an animal, it travels with it and falls off when `time_units_left` reaches `0`
+#[derive(Component, Reflect, Debug)]
+pub struct CarriedBy {
+    pub animal: Entity,
+    pub time_units_left: u32,
+}
+
+fn despawn_seeds(mut commands: Commands, seeds: Query<Entity, With<Seed>>) {
+    for seed_entity in seeds.iter() {
+        commands.entity(seed_entity).despawn_recursive();
+    }
+}
+
+fn destroy_eaten_seeds(
+    mut commands: Commands,
+    seeds: Query<(Entity, &Seed, &Transform), Without<CarriedBy>>,
+    tile_layout: Res<TileLayout>,
+    mut objects_in_tile_query: Query<&mut ObjectsInTile>,
+) {
+    for (seed_entity, seed, seed_transform) in seeds.iter() {
+        if seed.energy_data.mass <= 0.0 {
+            let tile_entity = tile_layout.get_tile_entity_for_transform(seed_transform);
+            objects_in_tile_query
+                .get_mut(tile_entity)
+                .expect("Failed to get tile under seed")
+                .remove_seed_entity(seed_entity);
+            commands.entity(seed_entity).despawn_recursive();
+        }
+    }
+}
+
+/// Seeds lying on a tile can stick to an animal on the same tile
+fn attach_seeds_to_animals(
+    mut commands: Commands,
+    mut tiles: Query<&mut ObjectsInTile>,
+    config: Res<SimulationConfig>,
+) {
+    if config.plant.animal_seed_dispersal_chance <= 0.0 {
+        return;
+    }
+
+    for mut objects_in_tile in tiles.iter_mut() {
+        let Some(&animal_entity) = objects_in_tile.animals.iter().next() else {
+            continue;
+        };
+
+        let attached_seeds: Vec<Entity> = objects_in_tile
+            .seeds
+            .iter()
+            .copied()
+            .filter(|_| rand::random::<f32>() < config.plant.animal_seed_dispersal_chance)
+            .collect();
+
+        for seed_entity in attached_seeds {
+            objects_in_tile.remove_seed_entity(seed_entity);
+            commands.entity(seed_entity).insert(CarriedBy {
+                animal: animal_entity,
+                time_units_left: config.plant.seed_carrying_time,
+            });
+        }
+    }
+}
+
+fn carry_seeds(
+    mut commands: Commands,
+    mut seeds: Query<(Entity, &mut CarriedBy, &mut Transform), With<Seed>>,
+    animals: Query<&Transform, (With<AnimalMarker>, Without<Seed>)>,
+    tile_layout: Res<TileLayout>,
+    mut objects_in_tile_query: Query<&mut ObjectsInTile>,
+) {
+    for (seed_entity, mut carried_by, mut transform) in seeds.iter_mut() {
+        carried_by.time_units_left = carried_by.time_units_left.saturating_sub(1);
+
+        // NOTE: seed falls off when the animal dies
+        let animal_transform = animals.get(carried_by.animal);
+        if let Ok(animal_transform) = animal_transform {
+            transform.translation.x = animal_transform.translation.x;
+            transform.translation.y = animal_transform.translation.y;
+        }
+        if animal_transform.is_ok() && carried_by.time_units_left > 0 {
+            continue;
+        }
+
+        commands.entity(seed_entity).remove::<CarriedBy>();
+        objects_in_tile_query
+            .get_mut(tile_layout.get_tile_entity_for_transform(&transform))
+            .expect("Failed to get tile under dropped seed")
+            .add_seed_entity(seed_entity);
+    }
+}
+
+/// Dormant seeds wait and die when they stop being viable, the others germinate on suitable, humid and nutrient-rich tiles
+fn germinate_seeds(
+    mut commands: Commands,
+    mut seeds: Query<(Entity, &mut Seed, &Mesh3d, &Transform, Has<CarriedBy>)>,
+    mut tiles: Query<(&BiomeType, &Humidity, &Nutrients, &mut ObjectsInTile)>,
+    plant_assets: Res<PlantAssets>,
+    tile_layout: Res<TileLayout>,
+    config: Res<SimulationConfig>,
+    mut offspring_lineage: OffspringLineage,
+) {
+    let mut choose_entity_observer = Observer::new(choose_entity_observer);
+    let mut seedlings_count = 0;
+
+    for (seed_entity, mut seed, mesh, transform, is_carried) in seeds.iter_mut() {
+        seed.viability_time_units_left = seed.viability_time_units_left.saturating_sub(1);
+        seed.dormancy_time_units_left = seed.dormancy_time_units_left.saturating_sub(1);
+
+        let tile_entity = tile_layout.get_tile_entity_for_transform(transform);
+        let (biome, humidity, nutrients, mut objects_in_tile) = tiles
+            .get_mut(tile_entity)
+            .expect("Failed to get tile under seed");
+
+        if seed.viability_time_units_left == 0 {
+            if !is_carried {
+                objects_in_tile.remove_seed_entity(seed_entity);
+            }
+            commands.entity(seed_entity).despawn_recursive();
+            continue;
+        }
+
+        let can_germinate = !is_carried
+            && seed.dormancy_time_units_left == 0
+            && seed.energy_data.mass > 0.0
+            && biome.plants_can_live_here()
+            && humidity.value >= config.plant.germination_min_humidity
+            && nutrients.value() >= config.plant.germination_min_nutrients;
+        if !can_germinate {
+            continue;
+        }
+
+        let new_size = seed.energy_data.get_size();
+        let transform =
+            Transform::from_translation(transform.translation.truncate().extend(new_size / 2.0))
+                .with_scale(Vec3::splat(new_size));
+
+        let mut entity_commands = commands.spawn((
+            BasicBundle {
+                mesh: mesh.clone(),
+                material: MeshMaterial3d(plant_assets.alive.clone()),
+                transform,
+            },
+            PlantBundle {
+                organism_bundle: OrganismBundle {
+                    health: seed.health.clone(),
+                    age: seed.age.clone(),
+                    senescence: seed.senescence.clone(),
+                    sexual_maturity: seed.sexual_maturity.clone(),
+                    energy_data: seed.energy_data.clone(),
+                    organism_energy_efficiency: seed.organism_energy_efficiency.clone(),
+                    thermal_tolerance: seed.thermal_tolerance.clone(),
+                    disease_resistance: seed.disease_resistance.clone(),
+                },
+                marker: PlantMarker,
+                matter_marker: PlantMatterMarker,
+                plant_energy_efficiency: seed.plant_energy_efficiency.clone(),
+                pollination_range: seed.pollination_range.clone(),
+                seed_dispersal: seed.seed_dispersal.clone(),
+                plant_height: seed.plant_height.clone(),
+                chemical_defense: seed.chemical_defense.clone(),
+            },
+            seed.species_id,
+            offspring_lineage.new_lineage(&seed.mother_lineage, &seed.father_lineage),
+        ));
+        // NOTE: separate sexes are inherited, but the sex itself is random
+        if seed.separate_sexes {
+            entity_commands.insert(Sex::random());
+        }
+        let plant_entity = entity_commands.id();
+
+        objects_in_tile.remove_seed_entity(seed_entity);
+        objects_in_tile.add_plant_entity(plant_entity);
+        commands.entity(seed_entity).despawn_recursive();
+        choose_entity_observer.watch_entity(plant_entity);
+        seedlings_count += 1;
+    }
+
+    if seedlings_count > 0 {
+        commands.spawn(choose_entity_observer);
+    }
+}
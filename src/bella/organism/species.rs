@@ -0,0 +1,456 @@
+use super::{
+    animal::{
+        animal_ai::Mobile, parental_care::ParentalCare, ActionRange, AnimalMarker, AttackDmg,
+        BiteSize, Digestion, Gestation, SightRange, ToxinTolerance,
+    },
+    disease::DiseaseResistance,
+    gene::Gene,
+    plant::{
+        seed::SeedDispersal, ChemicalDefense, PlantEnergyEfficiency, PlantHeight, PlantMarker,
+        PollinationRange,
+    },
+    Age, EnergyData, Health, OrganismEnergyEfficiency, Senescence, SexualMaturity,
+    ThermalTolerance,
+};
+use crate::bella::{
+    config::SimulationConfig,
+    restart::SimulationState,
+    time::{DayPassedEvent, SimulationTime},
+};
+use bevy::{ecs::query::QueryData, prelude::*, utils::HashMap};
+
+pub struct SpeciesPlugin;
+
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SpeciesId>()
+            .init_resource::<SpeciesRegistry>()
+            .add_event::<SpeciationEvent>()
+            .add_systems(
+                OnEnter(SimulationState::PreSimulation),
+                (reset_species_registry, update_species).chain(),
+            )
+            .add_systems(
+                Update,
+                update_species
+                    .run_if(on_event::<DayPassedEvent>)
+                    .run_if(in_state(SimulationState::Simulation)),
+            );
+    }
+}
+
+/// Species the organism belongs to. It's inherited from the first parent and changes only when a group of organisms splits off into a new species.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpeciesId(pub u32);
+
+#[derive(Debug, Clone)]
+pub struct Species {
+    pub parent: Option<SpeciesId>,
+    pub founded_on_day: u64,
+    /// plants and animals are never in the same species
+    pub is_animal: bool,
+    /// Average genome of all members, organisms too far from it can split off into a new species
+    pub centroid: Genome,
+    /// `0` means the species is extinct
+    pub population: usize,
+}
+
+/// All species that ever existed in the simulation, including extinct ones
+#[derive(Resource, Debug, Default)]
+pub struct SpeciesRegistry {
+    pub species: HashMap<SpeciesId, Species>,
+    next_id: u32,
+}
+
+impl SpeciesRegistry {
+    fn found_species(
+        &mut self,
+        parent: Option<SpeciesId>,
+        is_animal: bool,
+        centroid: Genome,
+        population: usize,
+        day: u64,
+    ) -> SpeciesId {
+        let species_id = SpeciesId(self.next_id);
+        self.next_id += 1;
+
+        self.species.insert(
+            species_id,
+            Species {
+                parent,
+                founded_on_day: day,
+                is_animal,
+                centroid,
+                population,
+            },
+        );
+
+        species_id
+    }
+}
+
+/// Sent when a group of organisms splits off from `parent` species
+#[derive(Event, Debug)]
+pub struct SpeciationEvent {
+    pub species: SpeciesId,
+    pub parent: SpeciesId,
+    pub population: usize,
+}
+
+/// Expression levels of all genes of an organism, always in the same order
+#[derive(Debug, Clone)]
+pub struct Genome(Vec<f32>);
+
+impl Genome {
+    /// Mean absolute difference of gene expression levels, from `0.0` for identical genomes to `1.0`.
+    /// Genomes with different sets of genes (e.g. plant and animal) are as distant as possible.
+    pub fn distance(&self, other: &Genome) -> f32 {
+        if self.0.len() != other.0.len() || self.0.is_empty() {
+            return 1.0;
+        }
+
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f32>()
+            / self.0.len() as f32
+    }
+
+    fn centroid<'a>(genomes: impl Iterator<Item = &'a Genome>) -> Genome {
+        let mut sum: Vec<f32> = Vec::new();
+        let mut count = 0;
+
+        for genome in genomes {
+            if sum.is_empty() {
+                sum = vec![0.0; genome.0.len()];
+            }
+            sum.iter_mut()
+                .zip(genome.0.iter())
+                .for_each(|(sum, expression_level)| *sum += expression_level);
+            count += 1;
+        }
+
+        Genome(sum.into_iter().map(|sum| sum / count as f32).collect())
+    }
+}
+
+#[derive(QueryData)]
+pub struct GenomeQuery {
+    health: &'static Health,
+    age: &'static Age,
+    senescence: &'static Senescence,
+    sexual_maturity: &'static SexualMaturity,
+    energy_data: &'static EnergyData,
+    organism_energy_efficiency: &'static OrganismEnergyEfficiency,
+    thermal_tolerance: &'static ThermalTolerance,
+    disease_resistance: &'static DiseaseResistance,
+    animal_genes: Option<(
+        &'static Mobile,
+        &'static SightRange,
+        &'static ActionRange,
+        &'static AttackDmg,
+        &'static BiteSize,
+        &'static ToxinTolerance,
+        &'static Digestion,
+        &'static Gestation,
+        &'static ParentalCare,
+    )>,
+    plant_genes: Option<(
+        &'static PlantEnergyEfficiency,
+        &'static PollinationRange,
+        &'static SeedDispersal,
+        &'static PlantHeight,
+        &'static ChemicalDefense,
+    )>,
+}
+
+impl GenomeQueryItem<'_> {
+    pub fn genome(&self) -> Genome {
+        let mut genes = vec![
+            &self.health.max_hp_gene.gene,
+            &self.health.regeneration_gene.gene,
+            &self.age.age_penalty_gene.gene,
+            &self.senescence.lifespan_gene.gene,
+            &self.sexual_maturity.maturity_age_gene.gene,
+            &self.sexual_maturity.reproduction_cooldown_gene.gene,
+            &self.sexual_maturity.max_reproduction_count_gene.gene,
+            &self.energy_data.max_active_energy_gene.gene,
+            &self.energy_data.energy_per_mass_unit_gene.gene,
+            &self
+                .organism_energy_efficiency
+                .energy_consumption_to_survive_per_mass_unit_gene
+                .gene,
+            &self
+                .organism_energy_efficiency
+                .reproduction_energy_cost_gene
+                .gene,
+            &self.thermal_tolerance.optimal_temperature_gene.gene,
+            &self.thermal_tolerance.tolerance_width_gene.gene,
+            &self.disease_resistance.gene.gene,
+        ];
+
+        for curve in [
+            &self.senescence.speed,
+            &self.senescence.sight,
+            &self.senescence.attack,
+            &self.senescence.fertility,
+            &self.senescence.photosynthesis,
+        ] {
+            genes.extend([&curve.onset_gene.gene, &curve.rate_gene.gene]);
+        }
+
+        if let Some((
+            mobile,
+            sight_range,
+            action_range,
+            attack,
+            bite_size,
+            toxin_tolerance,
+            digestion,
+            gestation,
+            parental_care,
+        )) = self.animal_genes
+        {
+            genes.extend([
+                &mobile.speed.gene,
+                &sight_range.gene.gene,
+                &action_range.gene.gene,
+                &attack.gene.female_gene.gene,
+                &attack.gene.male_gene.gene,
+                &bite_size.gene.gene,
+                &toxin_tolerance.gene.gene,
+                &digestion.plant_digestion_gene.gene,
+                &digestion.meat_digestion_gene.gene,
+                &gestation.gestation_period_gene.gene,
+                &gestation.litter_size_gene.gene,
+                &parental_care.care_duration_gene.gene,
+            ]);
+        }
+
+        if let Some((
+            plant_energy_efficiency,
+            pollination_range,
+            seed_dispersal,
+            plant_height,
+            chemical_defense,
+        )) = self.plant_genes
+        {
+            genes.extend([
+                &plant_energy_efficiency.production_from_solar_gene.gene,
+                &plant_energy_efficiency.nutrient_consumption.gene,
+                &pollination_range.gene.gene,
+                &seed_dispersal.distance_gene.gene,
+                &plant_height.gene.gene,
+                &chemical_defense.toxicity_gene.gene,
+            ]);
+        }
+
+        Genome(genes.into_iter().map(Gene::expression_level).collect())
+    }
+}
+
+/// Greedy clustering: every genome joins the first cluster with a leader close enough, otherwise it becomes a leader of a new cluster.
+/// If `leader` is given, it leads the first cluster, even if no genome ends up in it.
+fn cluster_genomes(genomes: &[Genome], leader: Option<&Genome>, threshold: f32) -> Vec<Vec<usize>> {
+    let mut leaders: Vec<&Genome> = leader.into_iter().collect();
+    let mut clusters: Vec<Vec<usize>> = vec![Vec::new(); leaders.len()];
+
+    for (index, genome) in genomes.iter().enumerate() {
+        match leaders
+            .iter()
+            .position(|leader| leader.distance(genome) <= threshold)
+        {
+            Some(cluster_index) => clusters[cluster_index].push(index),
+            None => {
+                leaders.push(genome);
+                clusters.push(vec![index]);
+            }
+        }
+    }
+
+    clusters
+}
+
+fn reset_species_registry(mut registry: ResMut<SpeciesRegistry>) {
+    *registry = SpeciesRegistry::default();
+}
+
+/// Organisms without species (the starting population) are clustered into new species.
+/// For existing species the biggest cluster keeps the id and clusters big enough split off into new species, the rest stays.
+fn update_species(
+    mut commands: Commands,
+    organisms: Query<
+        (Entity, GenomeQuery, Option<&SpeciesId>, Has<AnimalMarker>),
+        Or<(With<AnimalMarker>, With<PlantMarker>)>,
+    >,
+    mut registry: ResMut<SpeciesRegistry>,
+    mut speciation_event_writer: EventWriter<SpeciationEvent>,
+    config: Res<SimulationConfig>,
+    time: Res<SimulationTime>,
+) {
+    let threshold = config.organism.species_distance_threshold;
+    let day = time.days_passed();
+
+    // NOTE: plants and animals are never in the same species
+    let mut groups: HashMap<(Option<SpeciesId>, bool), (Vec<Entity>, Vec<Genome>)> = HashMap::new();
+    for (entity, genome_query_item, species_id, is_animal) in organisms.iter() {
+        let (entities, genomes) = groups.entry((species_id.copied(), is_animal)).or_default();
+        entities.push(entity);
+        genomes.push(genome_query_item.genome());
+    }
+
+    registry
+        .species
+        .values_mut()
+        .for_each(|species| species.population = 0);
+
+    for ((species_id, is_animal), (entities, genomes)) in groups {
+        let Some(species_id) = species_id else {
+            for cluster in cluster_genomes(&genomes, None, threshold) {
+                let centroid = Genome::centroid(cluster.iter().map(|&index| &genomes[index]));
+                let new_species_id =
+                    registry.found_species(None, is_animal, centroid, cluster.len(), day);
+
+                for index in cluster {
+                    commands.entity(entities[index]).try_insert(new_species_id);
+                }
+            }
+            continue;
+        };
+
+        let leader = registry
+            .species
+            .get(&species_id)
+            .map(|species| species.centroid.clone());
+        let clusters = cluster_genomes(&genomes, leader.as_ref(), threshold);
+
+        let main_cluster_index = clusters
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, cluster)| cluster.len())
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+
+        let mut remaining_members = Vec::new();
+        for (cluster_index, cluster) in clusters.into_iter().enumerate() {
+            if cluster_index == main_cluster_index
+                || cluster.len() < config.organism.min_new_species_population
+            {
+                remaining_members.extend(cluster);
+                continue;
+            }
+
+            let centroid = Genome::centroid(cluster.iter().map(|&index| &genomes[index]));
+            let new_species_id =
+                registry.found_species(Some(species_id), is_animal, centroid, cluster.len(), day);
+
+            for &index in &cluster {
+                commands.entity(entities[index]).try_insert(new_species_id);
+            }
+
+            speciation_event_writer.send(SpeciationEvent {
+                species: new_species_id,
+                parent: species_id,
+                population: cluster.len(),
+            });
+        }
+
+        let centroid = Genome::centroid(remaining_members.iter().map(|&index| &genomes[index]));
+        if let Some(species) = registry.species.get_mut(&species_id) {
+            species.centroid = centroid;
+            species.population = remaining_members.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bella::{config::FloatGeneConfig, organism::gene::FloatGene};
+    use rand::Rng;
+
+    const GENES_COUNT: usize = 30;
+    const POPULATION_SIZE: usize = 20;
+    const MAX_GENERATIONS: usize = 1000;
+    const DISTANCE_THRESHOLD: f32 = 0.1;
+    const MIN_NEW_SPECIES_POPULATION: usize = 5;
+
+    fn genome(genes: &[FloatGene]) -> Genome {
+        Genome(
+            genes
+                .iter()
+                .map(|gene| gene.gene.expression_level())
+                .collect(),
+        )
+    }
+
+    /// Random mating inside the population, every organism is replaced by an offspring
+    fn next_generation(population: &[Vec<FloatGene>]) -> Vec<Vec<FloatGene>> {
+        let mut rng = rand::thread_rng();
+
+        (0..population.len())
+            .map(|_| {
+                let parent1 = &population[rng.gen_range(0..population.len())];
+                let parent2 = &population[rng.gen_range(0..population.len())];
+
+                parent1
+                    .iter()
+                    .zip(parent2)
+                    .map(|(gene1, gene2)| gene1.mixed_with(gene2))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Number of clusters that would split off into new species, like in `update_species`
+    fn count_new_species(genomes: &[Genome], centroid: &Genome) -> usize {
+        let clusters = cluster_genomes(genomes, Some(centroid), DISTANCE_THRESHOLD);
+        let main_cluster_index = clusters
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, cluster)| cluster.len())
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+
+        clusters
+            .iter()
+            .enumerate()
+            .filter(|&(index, cluster)| {
+                index != main_cluster_index && cluster.len() >= MIN_NEW_SPECIES_POPULATION
+            })
+            .count()
+    }
+
+    #[test]
+    fn isolated_populations_split_into_new_species() {
+        let founder: Vec<FloatGene> = (0..GENES_COUNT)
+            .map(|_| FloatGeneConfig::new(1.0, 0.0).into())
+            .collect();
+        let mut populations = vec![vec![founder.clone(); POPULATION_SIZE]; 2];
+        let mut centroid = genome(&founder);
+
+        let generations_until_split = (0..MAX_GENERATIONS).find(|_| {
+            for population in populations.iter_mut() {
+                *population = next_generation(population);
+            }
+
+            let genomes: Vec<Genome> = populations
+                .iter()
+                .flatten()
+                .map(|genes| genome(genes))
+                .collect();
+            if count_new_species(&genomes, &centroid) > 0 {
+                return true;
+            }
+
+            centroid = Genome::centroid(genomes.iter());
+            false
+        });
+
+        assert!(
+            generations_until_split.is_some(),
+            "no species split off in {MAX_GENERATIONS} generations"
+        );
+    }
+}
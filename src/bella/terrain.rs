@@ -1,22 +1,41 @@
+pub mod biome_succession;
+pub mod overlays;
+pub mod rivers;
+pub mod spatial_query;
+pub mod terrain_file;
 pub mod terrain_overlay_state;
+pub mod terrain_texture;
 pub mod thermal_conductor;
 pub mod tile;
 
 use std::{cell::RefCell, collections::VecDeque};
 
+use self::biome_succession::{BiomeSuccession, BiomeSuccessionPlugin};
+use self::overlays::{OverlayLegend, OverlaysPlugin};
+use self::rivers::generate_rivers;
+use self::terrain_file::{save_terrain_file, SaveTerrainEvent, TerrainFile, TileData};
 use self::thermal_conductor::{
-    init_thermal_overlay_update_timer, update_temperatures, ThermalConductor,
-    ThermalConductorPlugin,
+    init_thermal_overlay_update_timer, ThermalConductor, ThermalConductorPlugin, TileTemperature,
+};
+use super::{
+    organism::{DamageSource, Health},
+    restart::SimulationState,
+    time::TimeUnitPassedEvent,
+    ui_facade::choose_tile_observer,
 };
-use super::{restart::SimulationState, time::TimeUnitPassedEvent};
 use crate::bella::config::SimulationConfig;
-use bevy::{prelude::*, utils::hashbrown::HashMap};
+use bevy::{
+    prelude::*,
+    utils::hashbrown::{HashMap, HashSet},
+};
 use noise::{
     utils::{NoiseMapBuilder, PlaneMapBuilder},
     HybridMulti, Perlin,
 };
 use rand::{rngs::ThreadRng, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 use terrain_overlay_state::{TerrainOverlayState, TerrainOverlayStatePlugin};
+use terrain_texture::TerrainTexture;
 use tile::{Tile, TileLayout};
 
 thread_local! {
@@ -28,11 +47,16 @@ pub struct TerrainPlugin;
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((ThermalConductorPlugin, TerrainOverlayStatePlugin))
+            .add_plugins(BiomeSuccessionPlugin)
+            .add_plugins(OverlaysPlugin)
             .register_type::<BiomeType>()
             .register_type::<Tile>()
             .register_type::<Humidity>()
             .register_type::<Nutrients>()
+            .register_type::<Elevation>()
             .register_type::<ObjectsInTile>()
+            .add_event::<ChangeBiomeEvent>()
+            .add_event::<SaveTerrainEvent>()
             .add_systems(
                 OnEnter(SimulationState::LoadAssets),
                 initialize_assets_map_biomes,
@@ -43,9 +67,19 @@ impl Plugin for TerrainPlugin {
             )
             .add_systems(
                 OnEnter(SimulationState::TerrainGeneration),
-                (generate_terrain, update_humidity).chain(),
+                (
+                    generate_terrain,
+                    // NOTE: terrain loaded from file already has its rivers and humidity
+                    generate_rivers.run_if(terrain_file_is_not_used),
+                    update_humidity.run_if(terrain_file_is_not_used),
+                )
+                    .chain(),
             )
             .add_systems(OnExit(SimulationState::Simulation), despawn_terrain)
+            .add_systems(
+                OnEnter(TerrainOverlayState::Bioms),
+                repaint_all_tiles_for_biome,
+            )
             .add_systems(
                 Update,
                 update_tile_color_for_biome
@@ -54,7 +88,17 @@ impl Plugin for TerrainPlugin {
             )
             .add_systems(
                 Update,
-                (update_temperatures, reset_nutrients).run_if(on_event::<TimeUnitPassedEvent>),
+                reset_nutrients.run_if(on_event::<TimeUnitPassedEvent>),
+            )
+            .add_systems(
+                Update,
+                change_biomes.run_if(in_state(SimulationState::Simulation)),
+            )
+            .add_systems(
+                Update,
+                save_terrain_file
+                    .run_if(on_event::<SaveTerrainEvent>)
+                    .run_if(in_state(SimulationState::Simulation)),
             );
     }
 }
@@ -64,38 +108,59 @@ pub struct TerrainMarker;
 
 #[derive(Bundle)]
 pub struct TerrainBundle {
-    mesh: Mesh3d,
-    material: MeshMaterial3d<StandardMaterial>,
-    transform: Transform,
-
     marker: TerrainMarker,
     tile: Tile,
     biome: BiomeType,
+    elevation: Elevation,
     thermal_conductor: ThermalConductor,
+    temperature: TileTemperature,
     nutrients: Nutrients,
     humidity: Humidity,
     objects_in_tile: ObjectsInTile,
+    biome_succession: BiomeSuccession,
 }
 
-#[derive(Component, Reflect, Debug, Hash, PartialEq, Eq)]
+#[derive(Component, Reflect, Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BiomeType {
     Stone,
     Sand,
     Dirt,
     Grass,
     Water,
+    River,
 }
 
 impl BiomeType {
+    pub const ALL: [BiomeType; 6] = [
+        BiomeType::Stone,
+        BiomeType::Sand,
+        BiomeType::Dirt,
+        BiomeType::Grass,
+        BiomeType::Water,
+        BiomeType::River,
+    ];
+
     pub fn plants_can_live_here(&self) -> bool {
-        *self == BiomeType::Dirt
+        matches!(self, BiomeType::Dirt | BiomeType::Grass)
     }
 
+    /// NOTE: rivers are barriers for animals, the same as lakes
     pub fn animals_can_live_here(&self) -> bool {
-        *self != BiomeType::Water
+        !self.is_water()
+    }
+
+    /// source of humidity for surrounding tiles
+    pub fn is_water(&self) -> bool {
+        matches!(self, BiomeType::Water | BiomeType::River)
     }
 }
 
+/// Height of the terrain, rivers flow from higher to lower tiles
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct Elevation {
+    pub value: f32,
+}
+
 /// ensures that there are more plants (and maybe more animals?) near the water, so we don't have the same number of organisms everywhere (less homogenous?)
 #[derive(Component, Reflect, Debug, Clone)]
 pub struct Humidity {
@@ -117,10 +182,23 @@ impl Nutrients {
         }
     }
 
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn base_value(&self) -> f32 {
+        self.base_value
+    }
+
     pub fn restore_value(&mut self) {
         self.value = self.base_value;
     }
 
+    pub fn set_base_value(&mut self, base_value: f32) {
+        self.base_value = base_value;
+        self.value = base_value;
+    }
+
     pub fn take_part_of_nutrients(&mut self, nutrients_to_take: f32) -> f32 {
         let value_to_give = if self.value >= nutrients_to_take {
             nutrients_to_take
@@ -134,54 +212,68 @@ impl Nutrients {
     }
 }
 
-#[derive(Component, Reflect, Debug)]
+/// Requests changing biome of a tile, together with everything that depends on the biome
+#[derive(Event)]
+pub struct ChangeBiomeEvent {
+    pub tile_entity: Entity,
+    pub biome: BiomeType,
+}
+
+/// Spatial index of organisms, carcasses and seeds, every tile knows what's on it.
+/// Must be kept up to date whenever entity spawns, moves to another tile, dies or is despawned.
+#[derive(Component, Reflect, Debug, Default)]
 pub struct ObjectsInTile {
-    pub plants: Vec<Entity>,
-    pub animals: Vec<Entity>,
-    pub plant_carcasses: Vec<Entity>,
-    pub animal_carcasses: Vec<Entity>,
+    pub plants: HashSet<Entity>,
+    pub animals: HashSet<Entity>,
+    pub plant_carcasses: HashSet<Entity>,
+    pub animal_carcasses: HashSet<Entity>,
+    /// only seeds lying on the tile, not the ones carried by animals
+    pub seeds: HashSet<Entity>,
 }
 
-impl ObjectsInTile {
-    pub fn remove_any_entity(&mut self, entity: Entity) {
-        if let Some(index) = self
-            .plants
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.plants.swap_remove(index);
-            // info!("Removed any plant {}", entity);
-            return;
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Plant,
+    Animal,
+    PlantCarcass,
+    AnimalCarcass,
+    Seed,
+}
 
-        if let Some(index) = self
-            .animals
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.animals.swap_remove(index);
-            // info!("Removed any animal {}", entity);
-            return;
+impl ObjectsInTile {
+    pub fn get(&self, kind: ObjectKind) -> &HashSet<Entity> {
+        match kind {
+            ObjectKind::Plant => &self.plants,
+            ObjectKind::Animal => &self.animals,
+            ObjectKind::PlantCarcass => &self.plant_carcasses,
+            ObjectKind::AnimalCarcass => &self.animal_carcasses,
+            ObjectKind::Seed => &self.seeds,
         }
+    }
 
-        if let Some(index) = self
-            .plant_carcasses
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.plant_carcasses.swap_remove(index);
-            // info!("Removed any plant carcass {}", entity);
-            return;
+    fn get_mut(&mut self, kind: ObjectKind) -> &mut HashSet<Entity> {
+        match kind {
+            ObjectKind::Plant => &mut self.plants,
+            ObjectKind::Animal => &mut self.animals,
+            ObjectKind::PlantCarcass => &mut self.plant_carcasses,
+            ObjectKind::AnimalCarcass => &mut self.animal_carcasses,
+            ObjectKind::Seed => &mut self.seeds,
         }
+    }
 
-        if let Some(index) = self
-            .animal_carcasses
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.animal_carcasses.swap_remove(index);
-            // info!("Removed any animal carcass {}", entity);
-            return;
+    pub fn remove_any_entity(&mut self, entity: Entity) {
+        let kinds = [
+            ObjectKind::Plant,
+            ObjectKind::Animal,
+            ObjectKind::PlantCarcass,
+            ObjectKind::AnimalCarcass,
+            ObjectKind::Seed,
+        ];
+
+        for kind in kinds {
+            if self.get_mut(kind).remove(&entity) {
+                return;
+            }
         }
 
         warn!(
@@ -192,216 +284,215 @@ impl ObjectsInTile {
         warn!("Animal entities: {:?}", self.animals);
         warn!("Plant carcass entities: {:?}", self.plant_carcasses);
         warn!("Animal carcass entities: {:?}", self.animal_carcasses);
+        warn!("Seed entities: {:?}", self.seeds);
     }
 
-    pub fn remove_plant_entity(&mut self, entity: Entity) {
-        if let Some(index) = self
-            .plants
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.plants.swap_remove(index);
-            // info!("Removed plant {}", entity);
-        } else {
-            warn!("Cannot remove plant entity {:?} from ObjectsInTile", entity);
-            warn!("Plant entities: {:?}", self.plants);
+    pub fn remove_entity(&mut self, kind: ObjectKind, entity: Entity) {
+        if !self.get_mut(kind).remove(&entity) {
+            warn!(
+                "Cannot remove {:?} entity {:?} from ObjectsInTile",
+                kind, entity
+            );
+            warn!("{:?} entities: {:?}", kind, self.get(kind));
         }
     }
 
-    pub fn remove_animal_entity(&mut self, entity: Entity) {
-        if let Some(index) = self
-            .animals
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.animals.swap_remove(index);
-            // info!("Removed animal {}", entity);
-        } else {
+    pub fn add_entity(&mut self, kind: ObjectKind, entity: Entity) {
+        if !self.get_mut(kind).insert(entity) {
             warn!(
-                "Cannot remove animal entity {:?} from ObjectsInTile",
-                entity
+                "Cannot add {:?} entity {:?} to ObjectsInTile, as it has been added already",
+                kind, entity
             );
-            warn!("Animal entities: {:?}", self.animals);
         }
     }
 
+    pub fn remove_plant_entity(&mut self, entity: Entity) {
+        self.remove_entity(ObjectKind::Plant, entity);
+    }
+
+    pub fn remove_animal_entity(&mut self, entity: Entity) {
+        self.remove_entity(ObjectKind::Animal, entity);
+    }
+
     pub fn remove_animal_carcass_entity(&mut self, entity: Entity) {
-        if let Some(index) = self
-            .animal_carcasses
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.animal_carcasses.swap_remove(index);
-            // info!("Removed animal carcass{}", entity);
-        } else {
-            warn!(
-                "Cannot remove animal carcass entity {:?} from ObjectsInTile",
-                entity
-            );
-            warn!("Animal carcass entities: {:?}", self.animal_carcasses);
-        }
+        self.remove_entity(ObjectKind::AnimalCarcass, entity);
     }
 
     pub fn remove_plant_carcass_entity(&mut self, entity: Entity) {
-        if let Some(index) = self
-            .plant_carcasses
-            .iter()
-            .position(|&other_entity| other_entity == entity)
-        {
-            self.plant_carcasses.swap_remove(index);
-            // info!("Removed plant carcass {}", entity);
-        } else {
-            warn!(
-                "Cannot remove plant carcass entity {:?} from ObjectsInTile",
-                entity
-            );
-            warn!("Plant carcass entities: {:?}", self.plant_carcasses);
-        }
+        self.remove_entity(ObjectKind::PlantCarcass, entity);
+    }
+
+    pub fn remove_seed_entity(&mut self, entity: Entity) {
+        self.remove_entity(ObjectKind::Seed, entity);
     }
 
     pub fn add_plant_entity(&mut self, entity: Entity) {
-        if !self.plants.contains(&entity) {
-            self.plants.push(entity);
-            // info!("Added plant {}", entity);
-        } else {
-            warn!(
-                "Cannot add plant entity {:?} to ObjectsInTile, as it has been added already",
-                entity
-            );
-        }
+        self.add_entity(ObjectKind::Plant, entity);
     }
 
     pub fn add_animal_entity(&mut self, entity: Entity) {
-        if !self.animals.contains(&entity) {
-            self.animals.push(entity);
-            // info!("Added animal {}", entity);
-        } else {
-            warn!(
-                "Cannot add animal entity {:?} to ObjectsInTile, as it has been added already",
-                entity
-            );
-        }
+        self.add_entity(ObjectKind::Animal, entity);
     }
 
     pub fn add_plant_carcass_entity(&mut self, entity: Entity) {
-        if !self.plant_carcasses.contains(&entity) {
-            self.plant_carcasses.push(entity);
-            // info!("Added plant carcass {}", entity);
-        } else {
-            warn!(
-                "Cannot add plant carcass entity {:?} to ObjectsInTile, as it has been added already",
-                entity
-            );
-        }
+        self.add_entity(ObjectKind::PlantCarcass, entity);
     }
 
     pub fn add_animal_carcass_entity(&mut self, entity: Entity) {
-        if !self.animal_carcasses.contains(&entity) {
-            self.animal_carcasses.push(entity);
-            // info!("Added animal carcass {}", entity);
-        } else {
-            warn!(
-                "Cannot add animal carcass entity {:?} to ObjectsInTile, as it has been added already",
-                entity
-            );
-        }
+        self.add_entity(ObjectKind::AnimalCarcass, entity);
+    }
+
+    pub fn add_seed_entity(&mut self, entity: Entity) {
+        self.add_entity(ObjectKind::Seed, entity);
     }
 }
 
 fn generate_terrain(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
     config: Res<SimulationConfig>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     let rows_count = config.terrain.map_height;
     let cols_count = config.terrain.map_width;
 
-    let mut rng = rand::thread_rng();
-
-    let seed = RNG.with(|rng| rng.borrow_mut().gen::<u32>());
-    let noise_map = PlaneMapBuilder::new(HybridMulti::<Perlin>::new(seed))
-        .set_size(cols_count as usize, rows_count as usize)
-        .build();
-
-    // noise_map.write_to_file(std::path::Path::new("test.png"));
-
-    let mut tile_layout = TileLayout::new(rows_count, cols_count, config.terrain.tile_size);
-
-    let mesh = tile_layout.generate_mesh();
-    let mesh_handle = meshes.add(mesh);
-
-    // let mut choose_entity_observer = Observer::new(choose_entity_observer);
+    // NOTE: terrain file that can't be used is reported and a new terrain is generated instead
+    let tiles_data = match &config.terrain.terrain_file {
+        Some(path) => match TerrainFile::load(path) {
+            Ok(terrain_file)
+                if terrain_file.rows == rows_count && terrain_file.cols == cols_count =>
+            {
+                terrain_file.tiles
+            }
+            Ok(_) => {
+                error!(
+                    "Size of the terrain from {:?} doesn't match map size from terrain config",
+                    path
+                );
+                generate_tiles_data(&config)
+            }
+            Err(err) => {
+                error!("Can't load terrain from {:?}: {}", path, err);
+                generate_tiles_data(&config)
+            }
+        },
+        None => generate_tiles_data(&config),
+    };
+
+    let mut tile_layout = TileLayout::new(
+        rows_count,
+        cols_count,
+        config.terrain.tile_shape,
+        config.terrain.tile_size,
+        config.terrain.toroidal,
+    );
 
     for row in 0..rows_count {
-        tile_layout.add_new_row();
-
         for col in 0..cols_count {
             let tile = Tile { row, col };
-            let tile_position = tile_layout.get_tile_position(&tile);
-
-            let noise_value = noise_map.get_value(col as usize, row as usize);
-            // TODO: for now let's use only Dirt and Water. Need to generate bigger biomes
-            let biome = match noise_value {
-                x if x < 0.3 => BiomeType::Dirt,
-                x if x < 0.5 => BiomeType::Sand,
-                x if x < 1.0 => BiomeType::Water,
-                _ => BiomeType::Water,
-            };
-
-            let heat_capacity = ThermalConductor::default_heat_capacity();
-            let min_heat = heat_capacity * ThermalConductor::min_temperature();
-            let max_heat = heat_capacity * ThermalConductor::max_temperature();
-            let heat = rng.gen_range(min_heat..max_heat); // TODO: should we just remove heat? It's not really useful in any way
-            let k = ThermalConductor::default_thermal_conductivity();
-            let thermal_conductor = ThermalConductor {
-                heat,
-                heat_capacity,
-                thermal_conductivity: k,
+            let tile_data = &tiles_data[tile_layout.get_tile_index(col, row)];
+            let biome = tile_data.biome;
+
+            let biome_config = config.terrain.biomes.get(&biome);
+            let thermal_conductor = ThermalConductor::new(
+                config.terrain.starting_temperature,
+                biome_config.heat_capacity,
+                biome_config.thermal_conductivity,
+            );
+            let temperature = TileTemperature {
+                value: thermal_conductor.temperature(),
             };
 
-            let humidity = Humidity { value: 0.0 };
-            let nutrients = match biome {
-                BiomeType::Dirt => Nutrients::new(config.terrain.nutrients_per_tile_dirt),
-                BiomeType::Sand => Nutrients::new(-config.terrain.nutrients_per_tile_sand),
-                _ => Nutrients::new(0.0), // TODO: maybe just don't insert it? do Option<Nutrients> in bundle?
+            let humidity = Humidity {
+                value: tile_data.humidity,
             };
-            let objects_in_tile = ObjectsInTile {
-                plants: Vec::new(),
-                animals: Vec::new(),
-                plant_carcasses: Vec::new(),
-                animal_carcasses: Vec::new(),
-            };
-
-            let transform = Transform::from_xyz(tile_position.x, tile_position.y, 0.)
-                .with_scale(Vec3::splat(config.terrain.tile_size));
+            let nutrients = Nutrients::new(tile_data.nutrients);
+            let objects_in_tile = ObjectsInTile::default();
 
             let entity = commands
                 .spawn(TerrainBundle {
-                    mesh: Mesh3d(mesh_handle.clone()),
-                    material: MeshMaterial3d(materials.add(Color::linear_rgb(0.9, 0.3, 0.3))),
-                    transform,
                     marker: TerrainMarker,
                     tile,
                     biome,
+                    elevation: Elevation {
+                        value: tile_data.elevation,
+                    },
                     thermal_conductor,
+                    temperature,
                     nutrients,
                     humidity,
                     objects_in_tile,
+                    biome_succession: BiomeSuccession::default(),
                 })
                 .id();
 
-            // choose_entity_observer.watch_entity(entity);
-
-            tile_layout.add_new_tile_to_last_row(entity);
+            tile_layout.add_tile_entity(entity);
         }
     }
 
-    // commands.spawn(choose_entity_observer);
+    // NOTE: whole map is a single mesh, tile colors are stored in the texture (one pixel per tile)
+    let terrain_texture = TerrainTexture::new(cols_count, rows_count, &mut images, &mut materials);
 
+    let terrain_mesh_entity = commands
+        .spawn((
+            TerrainMarker,
+            Mesh3d(meshes.add(tile_layout.generate_mesh())),
+            MeshMaterial3d(terrain_texture.material.clone()),
+            Transform::default(),
+        ))
+        .id();
+
+    let mut choose_tile_observer = Observer::new(choose_tile_observer);
+    choose_tile_observer.watch_entity(terrain_mesh_entity);
+    commands.spawn(choose_tile_observer);
+
+    commands.insert_resource(terrain_texture);
     commands.insert_resource(tile_layout);
 }
 
+/// Random terrain from noise, without rivers and humidity which are added later
+fn generate_tiles_data(config: &SimulationConfig) -> Vec<TileData> {
+    let rows_count = config.terrain.map_height;
+    let cols_count = config.terrain.map_width;
+
+    let seed = RNG.with(|rng| rng.borrow_mut().gen::<u32>());
+    let noise_map = PlaneMapBuilder::new(HybridMulti::<Perlin>::new(seed))
+        .set_size(cols_count as usize, rows_count as usize)
+        .build();
+
+    // noise_map.write_to_file(std::path::Path::new("test.png"));
+
+    let mut tiles_data = Vec::with_capacity((rows_count * cols_count) as usize);
+
+    for row in 0..rows_count {
+        for col in 0..cols_count {
+            let noise_value = noise_map.get_value(col as usize, row as usize);
+            // NOTE: Grass isn't generated, it only appears through biome succession
+            let biome = match noise_value {
+                x if x < -0.6 => BiomeType::Stone,
+                x if x < 0.3 => BiomeType::Dirt,
+                x if x < 0.5 => BiomeType::Sand,
+                x if x < 1.0 => BiomeType::Water,
+                _ => BiomeType::Water,
+            };
+
+            tiles_data.push(TileData {
+                biome,
+                elevation: -noise_value as f32,
+                humidity: 0.0,
+                nutrients: config.terrain.biomes.get(&biome).nutrients,
+            });
+        }
+    }
+
+    tiles_data
+}
+
+fn terrain_file_is_not_used(config: Res<SimulationConfig>) -> bool {
+    config.terrain.terrain_file.is_none()
+}
+
 fn despawn_terrain(mut cmd: Commands, terrain: Query<Entity, With<TerrainMarker>>) {
     for terrain_entity in terrain.iter() {
         cmd.entity(terrain_entity).despawn_recursive();
@@ -410,39 +501,158 @@ fn despawn_terrain(mut cmd: Commands, terrain: Query<Entity, With<TerrainMarker>
 
 #[derive(Resource)]
 pub struct AssetsMapBiomes {
-    pub medium_type_materials: HashMap<BiomeType, Handle<StandardMaterial>>,
+    pub medium_type_colors: HashMap<BiomeType, Color>,
 }
 
-fn initialize_assets_map_biomes(
-    mut cmd: Commands,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    let medium_type_materials = HashMap::from([
-        (BiomeType::Stone, materials.add(Color::srgb(0.5, 0.5, 0.5))),
-        (BiomeType::Sand, materials.add(Color::srgb(0.9, 0.9, 0.2))),
-        (BiomeType::Dirt, materials.add(Color::srgb(0.8, 0.5, 0.2))),
-        (BiomeType::Grass, materials.add(Color::srgb(0.4, 0.9, 0.4))),
-        (BiomeType::Water, materials.add(Color::srgb(0.2, 0.4, 0.9))),
+fn initialize_assets_map_biomes(mut cmd: Commands) {
+    let medium_type_colors = HashMap::from([
+        (BiomeType::Stone, Color::srgb(0.5, 0.5, 0.5)),
+        (BiomeType::Sand, Color::srgb(0.9, 0.9, 0.2)),
+        (BiomeType::Dirt, Color::srgb(0.8, 0.5, 0.2)),
+        (BiomeType::Grass, Color::srgb(0.4, 0.9, 0.4)),
+        (BiomeType::Water, Color::srgb(0.2, 0.4, 0.9)),
+        (BiomeType::River, Color::srgb(0.3, 0.6, 1.0)),
     ]);
 
-    cmd.insert_resource(AssetsMapBiomes {
-        medium_type_materials,
-    });
+    cmd.insert_resource(AssetsMapBiomes { medium_type_colors });
+}
+
+fn paint_tiles_for_biome<'a>(
+    tiles: impl Iterator<Item = (&'a Tile, &'a BiomeType)>,
+    terrain_texture: &TerrainTexture,
+    assets_map: &AssetsMapBiomes,
+    images: &mut Assets<Image>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let mut tiles = tiles.peekable();
+    if tiles.peek().is_none() {
+        return;
+    }
+
+    let Some(image) = terrain_texture.get_image_mut(images, materials) else {
+        return;
+    };
+
+    for (tile, medium_type) in tiles {
+        terrain_texture.set_tile_color(image, tile, assets_map.medium_type_colors[medium_type]);
+    }
+}
+
+fn repaint_all_tiles_for_biome(
+    tiles: Query<(&Tile, &BiomeType)>,
+    terrain_texture: Option<Res<TerrainTexture>>,
+    assets_map: Res<AssetsMapBiomes>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut legend: ResMut<OverlayLegend>,
+) {
+    let mut entries: Vec<_> = assets_map
+        .medium_type_colors
+        .iter()
+        .map(|(biome, color)| (format!("{:?}", biome), *color))
+        .collect();
+    entries.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    *legend = OverlayLegend::categories("Biomes", entries);
+
+    // NOTE: overlay can be changed before terrain is generated
+    let Some(terrain_texture) = terrain_texture else {
+        return;
+    };
+
+    paint_tiles_for_biome(
+        tiles.iter(),
+        &terrain_texture,
+        &assets_map,
+        &mut images,
+        &mut materials,
+    );
 }
 
 fn update_tile_color_for_biome(
-    mut tiles: Query<(&mut MeshMaterial3d<StandardMaterial>, &BiomeType)>,
+    tiles: Query<(&Tile, &BiomeType), Changed<BiomeType>>,
+    terrain_texture: Option<Res<TerrainTexture>>,
     assets_map: Res<AssetsMapBiomes>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(terrain_texture) = terrain_texture else {
+        return;
+    };
+
+    paint_tiles_for_biome(
+        tiles.iter(),
+        &terrain_texture,
+        &assets_map,
+        &mut images,
+        &mut materials,
+    );
+}
+
+fn change_biomes(
+    mut commands: Commands,
+    mut event_reader: EventReader<ChangeBiomeEvent>,
+    mut tiles: Query<(
+        &mut BiomeType,
+        &mut Nutrients,
+        &mut ThermalConductor,
+        &mut ObjectsInTile,
+    )>,
+    mut healths: Query<&mut Health>,
+    config: Res<SimulationConfig>,
 ) {
-    for (mut mesh_material, medium_type) in tiles.iter_mut() {
-        mesh_material.0 = assets_map
-            .medium_type_materials
-            .get(medium_type)
-            .unwrap()
-            .clone();
+    for event in event_reader.read() {
+        let Ok((mut biome, mut nutrients, mut thermal_conductor, mut objects_in_tile)) =
+            tiles.get_mut(event.tile_entity)
+        else {
+            continue;
+        };
+
+        set_biome(
+            event.biome,
+            &mut biome,
+            &mut nutrients,
+            &mut thermal_conductor,
+            &config,
+        );
+
+        // NOTE: organisms that can't live in the new biome die and become carcasses like any other dead organism,
+        // seeds can't germinate there, so they are removed
+        let mut dying_organisms = Vec::new();
+        if !biome.plants_can_live_here() {
+            dying_organisms.extend(objects_in_tile.plants.iter().copied());
+            for seed in objects_in_tile.seeds.drain() {
+                commands.entity(seed).despawn_recursive();
+            }
+        }
+        if !biome.animals_can_live_here() {
+            dying_organisms.extend(objects_in_tile.animals.iter().copied());
+        }
+
+        for organism in dying_organisms {
+            if let Ok(mut health) = healths.get_mut(organism) {
+                let hp = health.hp.max(0.0);
+                health.take_damage(hp, DamageSource::Habitat);
+            }
+        }
     }
 }
 
+/// Changes biome of the tile together with all properties that depend on it
+pub fn set_biome(
+    new_biome: BiomeType,
+    biome: &mut BiomeType,
+    nutrients: &mut Nutrients,
+    thermal_conductor: &mut ThermalConductor,
+    config: &SimulationConfig,
+) {
+    let biome_config = config.terrain.biomes.get(&new_biome);
+
+    *biome = new_biome;
+    nutrients.set_base_value(biome_config.nutrients);
+    thermal_conductor.set_heat_capacity(biome_config.heat_capacity);
+    thermal_conductor.thermal_conductivity = biome_config.thermal_conductivity;
+}
+
 fn reset_nutrients(mut query: Query<&mut Nutrients>) {
     for mut tile_nutrients in query.iter_mut() {
         if tile_nutrients.value < tile_nutrients.base_value {
@@ -456,38 +666,32 @@ fn update_humidity(
     tile_layout: Res<TileLayout>,
     config: Res<SimulationConfig>,
 ) {
-    let mut tiles_map: Vec<Vec<_>> = tile_layout
+    // NOTE: indexed the same way as `TileLayout::entities`
+    let mut tiles_map: Vec<_> = tile_layout
         .entities
         .iter()
-        .map(|tile_row| {
-            tile_row
-                .iter()
-                .map(|tile_entity| unsafe {
-                    query
-                        .get_unchecked(*tile_entity)
-                        .expect("Failed to get tile by Entity")
-                })
-                .collect()
+        .map(|tile_entity| unsafe {
+            query
+                .get_unchecked(*tile_entity)
+                .expect("Failed to get tile by Entity")
         })
         .collect();
 
     let mut queue = VecDeque::new();
 
     // add all water tiles to queue and set their humidity
-    for (y, row) in tiles_map.iter_mut().enumerate() {
-        for (x, tile) in row.iter_mut().enumerate() {
-            if *tile.1 == BiomeType::Water {
-                tile.0.value = config.environment.water_humidity;
-                queue.push_back((x, y));
-            }
+    for tile in tiles_map.iter_mut() {
+        if tile.1.is_water() {
+            tile.0.value = config.environment.water_humidity;
+            queue.push_back((tile.2.col, tile.2.row));
         }
     }
 
     while let Some((x, y)) = queue.pop_front() {
-        let src_humidity = tiles_map[y][x].0.value;
+        let src_humidity = tiles_map[tile_layout.get_tile_index(x, y)].0.value;
 
-        for (nx, ny) in neighbors(x, y, tile_layout.cols as usize, tile_layout.rows as usize) {
-            let dist_humidity = &mut tiles_map[ny][nx].0;
+        for (nx, ny) in tile_layout.get_adjacent_tile_coordinates(x, y) {
+            let dist_humidity = &mut tiles_map[tile_layout.get_tile_index(nx, ny)].0;
 
             if dist_humidity.value >= src_humidity {
                 continue;
@@ -502,25 +706,3 @@ fn update_humidity(
         }
     }
 }
-
-fn neighbors(x: usize, y: usize, width: usize, height: usize) -> Vec<(usize, usize)> {
-    let mut neighbours = Vec::new();
-
-    if x > 0 {
-        neighbours.push((x - 1, y));
-    }
-
-    if x + 1 < width {
-        neighbours.push((x + 1, y));
-    }
-
-    if y > 0 {
-        neighbours.push((x, y - 1));
-    }
-
-    if y + 1 < height {
-        neighbours.push((x, y + 1));
-    }
-
-    neighbours
-}
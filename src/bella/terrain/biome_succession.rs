@@ -0,0 +1,103 @@
+use super::{BiomeType, ChangeBiomeEvent, Humidity, Nutrients, ObjectsInTile};
+use crate::bella::{config::SimulationConfig, restart::SimulationState, time::DayPassedEvent};
+use bevy::prelude::*;
+
+pub struct BiomeSuccessionPlugin;
+
+impl Plugin for BiomeSuccessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<BiomeSuccession>().add_systems(
+            Update,
+            progress_biome_succession
+                .run_if(on_event::<DayPassedEvent>)
+                .run_if(in_state(SimulationState::Simulation)),
+        );
+    }
+}
+
+/// Tracks for how many days in a row the tile had conditions for becoming another biome
+#[derive(Component, Reflect, Debug, Default)]
+pub struct BiomeSuccession {
+    pub next_biome: Option<BiomeType>,
+    pub days: u32,
+}
+
+fn progress_biome_succession(
+    mut tiles: Query<(
+        Entity,
+        &BiomeType,
+        &Humidity,
+        &Nutrients,
+        &ObjectsInTile,
+        &mut BiomeSuccession,
+    )>,
+    mut change_biome_ew: EventWriter<ChangeBiomeEvent>,
+    config: Res<SimulationConfig>,
+) {
+    for (tile_entity, biome, humidity, nutrients, objects_in_tile, mut succession) in
+        tiles.iter_mut()
+    {
+        let Some(next_biome) = get_next_biome(
+            biome,
+            humidity,
+            nutrients,
+            objects_in_tile.plants.len(),
+            &config,
+        ) else {
+            *succession = BiomeSuccession::default();
+            continue;
+        };
+
+        if succession.next_biome == Some(next_biome) {
+            succession.days += 1;
+        } else {
+            succession.next_biome = Some(next_biome);
+            succession.days = 1;
+        }
+
+        if succession.days >= get_days_needed_for(&next_biome, &config) {
+            change_biome_ew.send(ChangeBiomeEvent {
+                tile_entity,
+                biome: next_biome,
+            });
+            *succession = BiomeSuccession::default();
+        }
+    }
+}
+
+/// Biome that the tile is heading towards in current conditions, `None` if it's stable
+fn get_next_biome(
+    biome: &BiomeType,
+    humidity: &Humidity,
+    nutrients: &Nutrients,
+    plants_count: usize,
+    config: &SimulationConfig,
+) -> Option<BiomeType> {
+    let rules = &config.terrain.succession;
+
+    match biome {
+        BiomeType::Dirt | BiomeType::Grass if humidity.value < rules.sand_max_humidity => {
+            Some(BiomeType::Sand)
+        }
+        BiomeType::Dirt
+            if plants_count >= rules.grass_min_plants
+                && humidity.value >= rules.grass_min_humidity
+                && nutrients.value > 0.0 =>
+        {
+            Some(BiomeType::Grass)
+        }
+        BiomeType::Grass if plants_count <= rules.dirt_max_plants => Some(BiomeType::Dirt),
+        _ => None,
+    }
+}
+
+fn get_days_needed_for(biome: &BiomeType, config: &SimulationConfig) -> u32 {
+    let rules = &config.terrain.succession;
+
+    match biome {
+        BiomeType::Grass => rules.days_to_grass,
+        BiomeType::Dirt => rules.days_to_dirt,
+        BiomeType::Sand => rules.days_to_sand,
+        _ => u32::MAX,
+    }
+}
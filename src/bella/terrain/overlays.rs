@@ -0,0 +1,317 @@
+use super::{
+    terrain_overlay_state::{AnimalDensityFilter, TerrainOverlayState},
+    terrain_texture::TerrainTexture,
+    tile::Tile,
+    Humidity, Nutrients, ObjectsInTile,
+};
+use crate::bella::{
+    config::SimulationConfig,
+    organism::{
+        animal::AnimalMarker, carcass::Carcass, plant::PlantMarker, species::SpeciesId, EnergyData,
+    },
+    restart::SimulationState,
+};
+use bevy::prelude::*;
+
+/// Number of entries in the legend of gradient overlays
+const GRADIENT_LEGEND_STEPS: usize = 5;
+
+pub struct OverlaysPlugin;
+
+impl Plugin for OverlaysPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OverlayLegend>()
+            .add_systems(
+                OnEnter(SimulationState::PreSimulation),
+                init_overlay_update_timer,
+            )
+            .add_systems(
+                Update,
+                tick_overlay_update_timer.run_if(in_state(SimulationState::Simulation)),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_humidity_overlay.run_if(in_state(TerrainOverlayState::Humidity)),
+                    update_nutrients_overlay.run_if(in_state(TerrainOverlayState::Nutrients)),
+                    update_plant_biomass_overlay
+                        .run_if(in_state(TerrainOverlayState::PlantBiomass)),
+                    update_animal_density_overlay
+                        .run_if(in_state(TerrainOverlayState::AnimalDensity)),
+                    update_carcass_mass_overlay.run_if(in_state(TerrainOverlayState::CarcassMass)),
+                )
+                    .run_if(overlay_needs_update)
+                    .run_if(in_state(SimulationState::Simulation))
+                    .after(tick_overlay_update_timer),
+            );
+    }
+}
+
+/// Colors shown next to the map, explaining what the current overlay shows
+#[derive(Resource, Debug, Default)]
+pub struct OverlayLegend {
+    pub title: String,
+    pub entries: Vec<(String, Color)>,
+}
+
+impl OverlayLegend {
+    pub fn categories(title: &str, entries: Vec<(String, Color)>) -> Self {
+        Self {
+            title: title.to_string(),
+            entries,
+        }
+    }
+
+    pub fn gradient(title: &str, min: f32, max: f32, gradient: &ColorGradient) -> Self {
+        let entries = (0..GRADIENT_LEGEND_STEPS)
+            .map(|step| {
+                let t = step as f32 / (GRADIENT_LEGEND_STEPS - 1) as f32;
+
+                (
+                    format!("{:.2}", min + (max - min) * t),
+                    gradient.get_color(t),
+                )
+            })
+            .collect();
+
+        Self {
+            title: title.to_string(),
+            entries,
+        }
+    }
+}
+
+/// Linear gradient between two colors, used by overlays showing numeric values
+pub struct ColorGradient {
+    pub from: Color,
+    pub to: Color,
+}
+
+impl ColorGradient {
+    /// `t` is clamped to `0.0..=1.0`
+    pub fn get_color(&self, t: f32) -> Color {
+        self.from.mix(&self.to, t.clamp(0.0, 1.0))
+    }
+}
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct OverlayUpdateTimer(Timer);
+
+fn init_overlay_update_timer(mut cmd: Commands, config: Res<SimulationConfig>) {
+    cmd.insert_resource(OverlayUpdateTimer(Timer::from_seconds(
+        config.terrain.overlay_update_cooldown,
+        TimerMode::Repeating,
+    )));
+}
+
+fn tick_overlay_update_timer(mut timer: ResMut<OverlayUpdateTimer>, time: Res<Time>) {
+    timer.tick(time.delta());
+}
+
+/// Overlay is repainted periodically and right after it's selected, so the old one doesn't stay on screen
+fn overlay_needs_update(
+    timer: Res<OverlayUpdateTimer>,
+    overlay_state: Res<State<TerrainOverlayState>>,
+    animal_density_filter: Res<AnimalDensityFilter>,
+) -> bool {
+    timer.just_finished() || overlay_state.is_changed() || animal_density_filter.is_changed()
+}
+
+/// Paints tiles with colors from the gradient, scaled between the lowest and the highest value
+fn paint_gradient_overlay(
+    values: Vec<(&Tile, f32)>,
+    title: &str,
+    gradient: &ColorGradient,
+    terrain_texture: &TerrainTexture,
+    images: &mut Assets<Image>,
+    materials: &mut Assets<StandardMaterial>,
+    legend: &mut OverlayLegend,
+) {
+    let min = values
+        .iter()
+        .map(|(_, value)| *value)
+        .reduce(f32::min)
+        .unwrap_or(0.0);
+    let max = values
+        .iter()
+        .map(|(_, value)| *value)
+        .reduce(f32::max)
+        .unwrap_or(0.0);
+
+    *legend = OverlayLegend::gradient(title, min, max, gradient);
+
+    let Some(image) = terrain_texture.get_image_mut(images, materials) else {
+        return;
+    };
+
+    for (tile, value) in values {
+        // NOTE: all tiles have the same value, just use the start of the gradient
+        let t = if max - min > f32::EPSILON {
+            (value - min) / (max - min)
+        } else {
+            0.0
+        };
+
+        terrain_texture.set_tile_color(image, tile, gradient.get_color(t));
+    }
+}
+
+fn update_humidity_overlay(
+    tiles: Query<(&Tile, &Humidity)>,
+    terrain_texture: Res<TerrainTexture>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut legend: ResMut<OverlayLegend>,
+) {
+    let values = tiles
+        .iter()
+        .map(|(tile, humidity)| (tile, humidity.value))
+        .collect();
+
+    paint_gradient_overlay(
+        values,
+        "Humidity",
+        &ColorGradient {
+            from: Color::srgb(0.9, 0.85, 0.7),
+            to: Color::srgb(0.1, 0.2, 0.8),
+        },
+        &terrain_texture,
+        &mut images,
+        &mut materials,
+        &mut legend,
+    );
+}
+
+fn update_nutrients_overlay(
+    tiles: Query<(&Tile, &Nutrients)>,
+    terrain_texture: Res<TerrainTexture>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut legend: ResMut<OverlayLegend>,
+) {
+    let values = tiles
+        .iter()
+        .map(|(tile, nutrients)| (tile, nutrients.value()))
+        .collect();
+
+    paint_gradient_overlay(
+        values,
+        "Nutrients",
+        &ColorGradient {
+            from: Color::srgb(0.3, 0.2, 0.1),
+            to: Color::srgb(0.2, 0.9, 0.2),
+        },
+        &terrain_texture,
+        &mut images,
+        &mut materials,
+        &mut legend,
+    );
+}
+
+fn update_plant_biomass_overlay(
+    tiles: Query<(&Tile, &ObjectsInTile)>,
+    plants: Query<&EnergyData, With<PlantMarker>>,
+    terrain_texture: Res<TerrainTexture>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut legend: ResMut<OverlayLegend>,
+) {
+    let values = tiles
+        .iter()
+        .map(|(tile, objects_in_tile)| {
+            let biomass = plants
+                .iter_many(&objects_in_tile.plants)
+                .map(|energy_data| energy_data.mass)
+                .sum();
+
+            (tile, biomass)
+        })
+        .collect();
+
+    paint_gradient_overlay(
+        values,
+        "Plant biomass",
+        &ColorGradient {
+            from: Color::srgb(0.1, 0.1, 0.1),
+            to: Color::srgb(0.3, 1.0, 0.3),
+        },
+        &terrain_texture,
+        &mut images,
+        &mut materials,
+        &mut legend,
+    );
+}
+
+fn update_animal_density_overlay(
+    tiles: Query<(&Tile, &ObjectsInTile)>,
+    animals: Query<Option<&SpeciesId>, With<AnimalMarker>>,
+    animal_density_filter: Res<AnimalDensityFilter>,
+    terrain_texture: Res<TerrainTexture>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut legend: ResMut<OverlayLegend>,
+) {
+    let values = tiles
+        .iter()
+        .map(|(tile, objects_in_tile)| {
+            let animals_count = animals
+                .iter_many(&objects_in_tile.animals)
+                .filter(|&species_id| animal_density_filter.matches(species_id))
+                .count();
+
+            (tile, animals_count as f32)
+        })
+        .collect();
+
+    paint_gradient_overlay(
+        values,
+        &format!("Animal density ({})", animal_density_filter.label()),
+        &ColorGradient {
+            from: Color::srgb(0.1, 0.1, 0.1),
+            to: Color::srgb(1.0, 0.3, 0.1),
+        },
+        &terrain_texture,
+        &mut images,
+        &mut materials,
+        &mut legend,
+    );
+}
+
+fn update_carcass_mass_overlay(
+    tiles: Query<(&Tile, &ObjectsInTile)>,
+    carcasses: Query<&Carcass>,
+    terrain_texture: Res<TerrainTexture>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut legend: ResMut<OverlayLegend>,
+) {
+    let values = tiles
+        .iter()
+        .map(|(tile, objects_in_tile)| {
+            let carcass_mass = carcasses
+                .iter_many(
+                    objects_in_tile
+                        .plant_carcasses
+                        .iter()
+                        .chain(objects_in_tile.animal_carcasses.iter()),
+                )
+                .map(|carcass| carcass.mass)
+                .sum();
+
+            (tile, carcass_mass)
+        })
+        .collect();
+
+    paint_gradient_overlay(
+        values,
+        "Carcass mass",
+        &ColorGradient {
+            from: Color::srgb(0.1, 0.1, 0.1),
+            to: Color::srgb(0.7, 0.3, 0.7),
+        },
+        &terrain_texture,
+        &mut images,
+        &mut materials,
+        &mut legend,
+    );
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+
+use super::{
+    set_biome,
+    thermal_conductor::ThermalConductor,
+    tile::{Tile, TileLayout},
+    BiomeType, Elevation, Nutrients,
+};
+use crate::bella::config::SimulationConfig;
+use bevy::{prelude::*, utils::hashbrown::HashSet};
+use rand::{rngs::ThreadRng, seq::SliceRandom, thread_rng};
+
+thread_local! {
+    static RNG: RefCell<ThreadRng> = RefCell::new(thread_rng());
+}
+
+/// Traces rivers downhill from random high tiles, until they reach water, other river or map edge.
+/// If river gets stuck in a pit (no lower neighbour), the pit becomes a lake.
+pub fn generate_rivers(
+    mut tiles: Query<(
+        &Tile,
+        &Elevation,
+        &mut BiomeType,
+        &mut Nutrients,
+        &mut ThermalConductor,
+    )>,
+    tile_layout: Res<TileLayout>,
+    config: Res<SimulationConfig>,
+) {
+    let rivers_config = &config.terrain.rivers;
+
+    let mut sources: Vec<Entity> = tile_layout
+        .entities
+        .iter()
+        .copied()
+        .filter(|&tile_entity| {
+            let (_, elevation, biome, _, _) = tiles
+                .get(tile_entity)
+                .expect("Failed to get tile by Entity");
+
+            !biome.is_water() && elevation.value >= rivers_config.source_min_elevation
+        })
+        .collect();
+
+    RNG.with(|rng| sources.shuffle(&mut *rng.borrow_mut()));
+    sources.truncate(rivers_config.count);
+
+    for source in sources {
+        let mut river_path = HashSet::new();
+        let mut current = source;
+
+        for _ in 0..rivers_config.max_length {
+            let (tile, elevation, biome, _, _) =
+                tiles.get(current).expect("Failed to get tile by Entity");
+
+            // NOTE: river joined another river or reached lake/sea
+            if biome.is_water() {
+                break;
+            }
+
+            let adjacent_coordinates =
+                tile_layout.get_adjacent_tile_coordinates(tile.col, tile.row);
+            let is_on_map_edge = adjacent_coordinates.len() < tile_layout.max_adjacent_tiles();
+            let elevation = elevation.value;
+
+            let lowest_neighbour = adjacent_coordinates
+                .into_iter()
+                .map(|(col, row)| tile_layout.get_tile_entity(col, row))
+                .filter(|neighbour| !river_path.contains(neighbour))
+                .map(|neighbour| {
+                    let (_, neighbour_elevation, _, _, _) =
+                        tiles.get(neighbour).expect("Failed to get tile by Entity");
+
+                    (neighbour, neighbour_elevation.value)
+                })
+                .min_by(|(_, elevation1), (_, elevation2)| elevation1.total_cmp(elevation2));
+
+            river_path.insert(current);
+
+            let (_, _, mut biome, mut nutrients, mut thermal_conductor) = tiles
+                .get_mut(current)
+                .expect("Failed to get tile by Entity");
+
+            match lowest_neighbour {
+                Some((neighbour, neighbour_elevation)) if neighbour_elevation < elevation => {
+                    set_biome(
+                        BiomeType::River,
+                        &mut biome,
+                        &mut nutrients,
+                        &mut thermal_conductor,
+                        &config,
+                    );
+                    current = neighbour;
+                }
+                // NOTE: river flows out of the map
+                _ if is_on_map_edge => {
+                    set_biome(
+                        BiomeType::River,
+                        &mut biome,
+                        &mut nutrients,
+                        &mut thermal_conductor,
+                        &config,
+                    );
+                    break;
+                }
+                _ => {
+                    set_biome(
+                        BiomeType::Water,
+                        &mut biome,
+                        &mut nutrients,
+                        &mut thermal_conductor,
+                        &config,
+                    );
+                    break;
+                }
+            }
+        }
+    }
+}
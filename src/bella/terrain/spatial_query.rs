@@ -0,0 +1,130 @@
+use super::{tile::TileLayout, ObjectKind, ObjectsInTile};
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+/// Queries for organisms and carcasses around some position, built on top of `ObjectsInTile`.
+/// Distances are real distances between entities (wrapped on toroidal maps), not distances between tiles.
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, 's> {
+    tile_layout: Res<'w, TileLayout>,
+    objects_in_tiles: Query<'w, 's, &'static ObjectsInTile>,
+    transforms: Query<'w, 's, &'static Transform>,
+}
+
+impl SpatialQuery<'_, '_> {
+    /// All entities of given kinds in the circle that pass the filter, together with their distance to the center
+    pub fn query_circle(
+        &self,
+        position: Vec2,
+        range: f32,
+        kinds: &[ObjectKind],
+        filter: impl Fn(Entity) -> bool,
+    ) -> Vec<(Entity, f32)> {
+        self.tile_layout
+            .get_tile_entities_in_range(position, range)
+            .iter()
+            .filter_map(|&tile_entity| self.objects_in_tiles.get(tile_entity).ok())
+            .flat_map(|objects_in_tile| {
+                kinds
+                    .iter()
+                    .flat_map(move |&kind| objects_in_tile.get(kind).iter().copied())
+            })
+            .filter_map(|entity| {
+                let distance = self.get_distance_to_entity(position, entity)?;
+
+                (distance <= range).then_some((entity, distance))
+            })
+            .filter(|&(entity, _)| filter(entity))
+            .collect()
+    }
+
+    fn get_distance_to_entity(&self, position: Vec2, entity: Entity) -> Option<f32> {
+        // NOTE: entity could have been despawned this frame, just ignore it
+        let transform = self.transforms.get(entity).ok()?;
+
+        Some(
+            self.tile_layout
+                .get_distance(position, transform.translation.truncate()),
+        )
+    }
+
+    /// Nearest entity of given kinds in the circle that passes the filter.
+    /// Tiles are visited in rings around the position, until no further ring can contain anything nearer.
+    pub fn find_nearest(
+        &self,
+        position: Vec2,
+        range: f32,
+        kinds: &[ObjectKind],
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        if self
+            .tile_layout
+            .try_get_tile_entity_for_position(position)
+            .is_none()
+        {
+            return self
+                .query_circle(position, range, kinds, filter)
+                .into_iter()
+                .min_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
+                .map(|(entity, _)| entity);
+        }
+
+        let mut nearest: Option<(Entity, f32)> = None;
+        for ring in 0..=self.tile_layout.get_max_ring() {
+            let max_distance = nearest.map_or(range, |(_, distance)| distance);
+            if self.tile_layout.get_min_distance_to_ring(ring) > max_distance {
+                break;
+            }
+
+            for tile_entity in self.tile_layout.get_tile_entities_in_ring(position, ring) {
+                let Ok(objects_in_tile) = self.objects_in_tiles.get(tile_entity) else {
+                    continue;
+                };
+
+                for &kind in kinds {
+                    for &entity in objects_in_tile.get(kind) {
+                        let Some(distance) = self.get_distance_to_entity(position, entity) else {
+                            continue;
+                        };
+
+                        let is_nearer = nearest
+                            .map_or(distance <= range, |(_, nearest_distance)| {
+                                distance < nearest_distance
+                            });
+                        if is_nearer && filter(entity) {
+                            nearest = Some((entity, distance));
+                        }
+                    }
+                }
+            }
+        }
+
+        nearest.map(|(entity, _)| entity)
+    }
+
+    /// Up to `k` nearest entities of given kinds in the circle that pass the filter, sorted from the nearest
+    pub fn find_k_nearest(
+        &self,
+        position: Vec2,
+        range: f32,
+        k: usize,
+        kinds: &[ObjectKind],
+        filter: impl Fn(Entity) -> bool,
+    ) -> Vec<Entity> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut entities = self.query_circle(position, range, kinds, filter);
+        let compare_distances = |(_, distance1): &(Entity, f32), (_, distance2): &(Entity, f32)| {
+            distance1.total_cmp(distance2)
+        };
+        // NOTE: only the k nearest entities need to be sorted
+        if entities.len() > k {
+            entities.select_nth_unstable_by(k - 1, compare_distances);
+            entities.truncate(k);
+        }
+        entities.sort_by(compare_distances);
+
+        entities.into_iter().map(|(entity, _)| entity).collect()
+    }
+}
@@ -0,0 +1,81 @@
+use super::{tile::TileLayout, BiomeType, Elevation, Humidity, Nutrients};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+/// Requests saving current terrain, so it can be loaded later with `terrain_file` option in terrain config
+#[derive(Event)]
+pub struct SaveTerrainEvent {
+    pub path: PathBuf,
+}
+
+/// Terrain saved to a file, only data needed to recreate tiles is stored
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TerrainFile {
+    pub rows: u32,
+    pub cols: u32,
+    /// tiles row by row, the same order as in `TileLayout::entities`
+    pub tiles: Vec<TileData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileData {
+    pub biome: BiomeType,
+    pub elevation: f32,
+    pub humidity: f32,
+    pub nutrients: f32,
+}
+
+impl TerrainFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let file = File::open(path).map_err(|err| err.to_string())?;
+
+        rmp_serde::decode::from_read(BufReader::new(file)).map_err(|err| err.to_string())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let file = File::create(path).map_err(|err| err.to_string())?;
+
+        rmp_serde::encode::write(&mut BufWriter::new(file), self).map_err(|err| err.to_string())
+    }
+}
+
+pub fn save_terrain_file(
+    mut event_reader: EventReader<SaveTerrainEvent>,
+    tiles: Query<(&BiomeType, &Elevation, &Humidity, &Nutrients)>,
+    tile_layout: Res<TileLayout>,
+) {
+    for event in event_reader.read() {
+        let tiles = tile_layout
+            .entities
+            .iter()
+            .map(|&tile_entity| {
+                let (biome, elevation, humidity, nutrients) = tiles
+                    .get(tile_entity)
+                    .expect("Failed to get tile by Entity");
+
+                TileData {
+                    biome: *biome,
+                    elevation: elevation.value,
+                    humidity: humidity.value,
+                    nutrients: nutrients.base_value(),
+                }
+            })
+            .collect();
+
+        let terrain_file = TerrainFile {
+            rows: tile_layout.rows,
+            cols: tile_layout.cols,
+            tiles,
+        };
+
+        match terrain_file.save(&event.path) {
+            Ok(()) => info!("Terrain saved to {:?}", event.path),
+            Err(err) => error!("Can't save terrain to {:?}: {}", event.path, err),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::bella::ui_facade::EguiFocusState;
+use crate::bella::{
+    organism::species::{SpeciesId, SpeciesRegistry},
+    ui_facade::EguiFocusState,
+};
 
 pub struct TerrainOverlayStatePlugin;
 
@@ -9,20 +12,104 @@ pub enum TerrainOverlayState {
     #[default]
     Bioms,
     Thermal,
+    Humidity,
+    Nutrients,
+    PlantBiomass,
+    AnimalDensity,
+    CarcassMass,
+}
+
+impl TerrainOverlayState {
+    pub const ALL: [TerrainOverlayState; 7] = [
+        TerrainOverlayState::Bioms,
+        TerrainOverlayState::Thermal,
+        TerrainOverlayState::Humidity,
+        TerrainOverlayState::Nutrients,
+        TerrainOverlayState::PlantBiomass,
+        TerrainOverlayState::AnimalDensity,
+        TerrainOverlayState::CarcassMass,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TerrainOverlayState::Bioms => "Biomes (F1)",
+            TerrainOverlayState::Thermal => "Temperature (F2)",
+            TerrainOverlayState::Humidity => "Humidity (F3)",
+            TerrainOverlayState::Nutrients => "Nutrients (F4)",
+            TerrainOverlayState::PlantBiomass => "Plant biomass (F5)",
+            TerrainOverlayState::AnimalDensity => "Animal density (F6)",
+            TerrainOverlayState::CarcassMass => "Carcass mass (F7)",
+        }
+    }
+}
+
+/// Which animals are counted by `TerrainOverlayState::AnimalDensity`, `None` means all of them
+#[derive(Resource, Debug, Default)]
+pub struct AnimalDensityFilter {
+    pub species: Option<SpeciesId>,
+}
+
+impl AnimalDensityFilter {
+    /// All animals first, then living animal species from the oldest
+    pub fn options(registry: &SpeciesRegistry) -> Vec<Option<SpeciesId>> {
+        let mut species_ids: Vec<SpeciesId> = registry
+            .species
+            .iter()
+            .filter(|(_, species)| species.is_animal && species.population > 0)
+            .map(|(&species_id, _)| species_id)
+            .collect();
+        species_ids.sort_by_key(|species_id| species_id.0);
+
+        std::iter::once(None)
+            .chain(species_ids.into_iter().map(Some))
+            .collect()
+    }
+
+    /// NOTE: animals get their species a moment after they are born, until then only "all" counts them
+    pub fn matches(&self, species_id: Option<&SpeciesId>) -> bool {
+        self.species
+            .is_none_or(|filter_species_id| Some(&filter_species_id) == species_id)
+    }
+
+    pub fn label(&self) -> String {
+        Self::option_label(&self.species)
+    }
+
+    pub fn option_label(species_id: &Option<SpeciesId>) -> String {
+        match species_id {
+            None => "all".into(),
+            Some(species_id) => format!("species {}", species_id.0),
+        }
+    }
+
+    fn cycle(&mut self, registry: &SpeciesRegistry) {
+        let options = Self::options(registry);
+        let current_index = options
+            .iter()
+            .position(|species_id| *species_id == self.species)
+            .unwrap_or(0);
+
+        self.species = options[(current_index + 1) % options.len()];
+    }
 }
 
 impl Plugin for TerrainOverlayStatePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<TerrainOverlayState>().add_systems(
-            Update,
-            change_overlay_state_based_on_keyboard_input
-                .run_if(in_state(EguiFocusState::IsNotFocused)),
-        );
+        app.init_state::<TerrainOverlayState>()
+            .init_resource::<AnimalDensityFilter>()
+            .add_systems(
+                Update,
+                change_overlay_state_based_on_keyboard_input
+                    .run_if(in_state(EguiFocusState::IsNotFocused)),
+            );
     }
 }
 
 fn change_overlay_state_based_on_keyboard_input(
+    current_state: Res<State<TerrainOverlayState>>,
     mut next_state: ResMut<NextState<TerrainOverlayState>>,
+    mut animal_density_filter: ResMut<AnimalDensityFilter>,
+    species_registry: Res<SpeciesRegistry>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
 ) {
     if keyboard_input.just_pressed(KeyCode::F1) {
@@ -32,4 +119,29 @@ fn change_overlay_state_based_on_keyboard_input(
     if keyboard_input.just_pressed(KeyCode::F2) {
         next_state.set(TerrainOverlayState::Thermal);
     }
+
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        next_state.set(TerrainOverlayState::Humidity);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F4) {
+        next_state.set(TerrainOverlayState::Nutrients);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F5) {
+        next_state.set(TerrainOverlayState::PlantBiomass);
+    }
+
+    // NOTE: pressing F6 again switches to the next species
+    if keyboard_input.just_pressed(KeyCode::F6) {
+        if **current_state == TerrainOverlayState::AnimalDensity {
+            animal_density_filter.cycle(&species_registry);
+        } else {
+            next_state.set(TerrainOverlayState::AnimalDensity);
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F7) {
+        next_state.set(TerrainOverlayState::CarcassMass);
+    }
 }
@@ -0,0 +1,70 @@
+use super::tile::Tile;
+use bevy::{
+    image::ImageSampler,
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+/// Texture of the terrain mesh, every pixel is the color of one tile.
+/// Overlays change tile colors by writing pixels instead of swapping materials.
+#[derive(Resource)]
+pub struct TerrainTexture {
+    pub image: Handle<Image>,
+    pub material: Handle<StandardMaterial>,
+    cols: u32,
+}
+
+impl TerrainTexture {
+    pub fn new(
+        cols: u32,
+        rows: u32,
+        images: &mut Assets<Image>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Self {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: cols,
+                height: rows,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+        );
+        // NOTE: we don't want colors of neighbouring tiles to blend
+        image.sampler = ImageSampler::nearest();
+
+        let image = images.add(image);
+        let material = materials.add(StandardMaterial {
+            base_color_texture: Some(image.clone()),
+            ..default()
+        });
+
+        Self {
+            image,
+            material,
+            cols,
+        }
+    }
+
+    pub fn set_tile_color(&self, image: &mut Image, tile: &Tile, color: Color) {
+        let pixel_index = ((tile.row * self.cols + tile.col) * 4) as usize;
+
+        image.data[pixel_index..pixel_index + 4].copy_from_slice(&color.to_srgba().to_u8_array());
+    }
+
+    /// Get image to change tile colors. Material is also marked as changed, so the new texture is used for rendering.
+    pub fn get_image_mut<'a>(
+        &self,
+        images: &'a mut Assets<Image>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> Option<&'a mut Image> {
+        materials.get_mut(&self.material);
+
+        images.get_mut(&self.image)
+    }
+}
@@ -1,5 +1,7 @@
 use super::{
+    overlays::{ColorGradient, OverlayLegend},
     terrain_overlay_state::TerrainOverlayState,
+    terrain_texture::TerrainTexture,
     tile::{Tile, TileLayout},
 };
 use crate::bella::{
@@ -13,10 +15,7 @@ pub struct ThermalConductorPlugin;
 impl Plugin for ThermalConductorPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<ThermalConductor>()
-            .add_systems(
-                OnEnter(SimulationState::LoadAssets),
-                initialize_assets_map_temperature,
-            )
+            .register_type::<TileTemperature>()
             .add_systems(
                 Update,
                 update_tile_color_for_thermal
@@ -31,7 +30,13 @@ impl Plugin for ThermalConductorPlugin {
             // )
             .add_systems(
                 Update,
-                accumulate_energy_from_solar.run_if(on_event::<TimeUnitPassedEvent>),
+                (
+                    update_temperatures,
+                    accumulate_energy_from_solar,
+                    update_tile_temperatures,
+                )
+                    .chain()
+                    .run_if(on_event::<TimeUnitPassedEvent>),
             );
     }
 }
@@ -50,10 +55,27 @@ pub struct ThermalConductor {
 }
 
 impl ThermalConductor {
+    pub fn new(temperature: f32, heat_capacity: f32, thermal_conductivity: f32) -> Self {
+        let mut thermal_conductor = Self {
+            heat: temperature * heat_capacity,
+            heat_capacity,
+            thermal_conductivity,
+        };
+        thermal_conductor.clamp_heat();
+        thermal_conductor
+    }
+
     pub fn temperature(&self) -> f32 {
         self.heat / self.heat_capacity
     }
 
+    /// Changes heat capacity (e.g. when biome changes) without changing the temperature
+    pub fn set_heat_capacity(&mut self, heat_capacity: f32) {
+        let temperature = self.temperature();
+        self.heat_capacity = heat_capacity;
+        self.heat = temperature * heat_capacity;
+    }
+
     pub fn min_heat(&self) -> f32 {
         self.heat_capacity * ThermalConductor::min_temperature()
     }
@@ -63,11 +85,21 @@ impl ThermalConductor {
     }
 
     pub fn clamp_heat(&mut self) {
-        let _ = self.heat.clamp(self.min_heat(), self.max_heat());
+        self.heat = self.heat.clamp(self.min_heat(), self.max_heat());
     }
 
-    pub fn get_heat_lose(&self) -> f32 {
-        ThermalConductor::default_heat_lose() * self.temperature()
+    pub fn get_heat_lose(&self, heat_loss_per_degree: f32) -> f32 {
+        heat_loss_per_degree * self.temperature()
+    }
+
+    /// Conductivity between 2 tiles. Harmonic mean makes it symmetrical (so energy is conserved)
+    /// and lets the worse conductor limit the flow
+    pub fn conductivity_between(k1: f32, k2: f32) -> f32 {
+        if k1 + k2 <= 0.0 {
+            return 0.0;
+        }
+
+        2.0 * k1 * k2 / (k1 + k2)
     }
 
     pub const fn min_temperature() -> f32 {
@@ -77,18 +109,12 @@ impl ThermalConductor {
     pub const fn max_temperature() -> f32 {
         100.
     }
+}
 
-    pub const fn default_heat_capacity() -> f32 {
-        1000.
-    }
-
-    pub const fn default_thermal_conductivity() -> f32 {
-        20.
-    }
-
-    const fn default_heat_lose() -> f32 {
-        200.
-    }
+/// Temperature of the tile, updated after every step of thermal simulation. Read this instead of `ThermalConductor` in other systems
+#[derive(Component, Reflect, Debug, Clone)]
+pub struct TileTemperature {
+    pub value: f32,
 }
 
 #[derive(Resource, Deref, DerefMut)]
@@ -101,148 +127,95 @@ pub fn init_thermal_overlay_update_timer(mut cmd: Commands, config: Res<Simulati
     )));
 }
 
-pub fn update_temperatures(
-    mut query: Query<(&Tile, &mut ThermalConductor)>,
+fn update_temperatures(
+    mut query: Query<(Entity, &Tile, &mut ThermalConductor)>,
     tile_layout: Res<TileLayout>,
 ) {
-    // for (tile, mut thermal_conductor) in query.iter_mut() {
-    //     let neighbours: Vec<_> = tile_layout
-    //         .get_neighbour_entities(tile.col, tile.row)
-    //         .iter()
-    //         .flat_map(|&entity| query.get(entity).ok())
-    //         .collect();
-
-    // let heat_diff = neighbours
-    //     .iter()
-    //     .map(|(&tile, &mut thermal_conductor)| {
-    //         let temp_diff = media_org.temperature() - media_org[neighbour_i].temperature();
-    //         media_org[i].thermal_conductivity * temp_diff
-    //     })
-    //     .sum();
-    // }
-
-    // for x in 0..tile_layout.cols {
-    //     for y in 0..tile_layout.rows {
-    //         let neighbours: Vec<(&Tile, &mut ThermalConductor)> = tile_layout
-    //             .get_neighbour_entities(x, y)
-    //             .iter()
-    //             .filter_map(|entity| query.get(entity))
-    //             .collect();
-
-    //         let heat_diff = neighbours
-    //             .iter()
-    //             .map(|(&tile, &mut thermal_conductor)| {
-    //                 let temp_diff = media_org.temperature() - media_org[neighbour_i].temperature();
-    //                 media_org[i].thermal_conductivity * temp_diff
-    //             })
-    //             .sum();
-
-    //         heat_diffs.push(heat_diff);
-    //     }
-    // }
-
-    let (tiles, thermal_conductors): (Vec<_>, Vec<_>) = query.iter().unzip();
-
-    // we will set all heat at once to make sure order of iteration doesn't matter and energy is conserved
-    let mut heat_diffs: Vec<f32> = Vec::with_capacity(thermal_conductors.capacity());
-
-    for i in 0..thermal_conductors.len() {
-        let all_neighbours = tile_layout.get_neighbour_entities(tiles[i].col, tiles[i].row);
-
-        let heat_diff = all_neighbours
+    // we need temperatures from before this step to make sure order of iteration doesn't matter and energy is conserved
+    let snapshot: HashMap<Entity, (f32, f32)> = query
+        .iter()
+        .map(|(entity, _, thermal_conductor)| {
+            (
+                entity,
+                (
+                    thermal_conductor.temperature(),
+                    thermal_conductor.thermal_conductivity,
+                ),
+            )
+        })
+        .collect();
+
+    for (entity, tile, mut thermal_conductor) in query.iter_mut() {
+        let (temperature, conductivity) = snapshot[&entity];
+
+        let heat_diff: f32 = tile_layout
+            .get_neighbour_entities(tile.col, tile.row)
             .iter()
-            .map(|&neighbour_entity| {
-                let (_, neighbour_thermal_conductor) = query
-                    .get(neighbour_entity)
-                    .expect("This entity doesn't exist (it should)");
-                let temp_diff =
-                    thermal_conductors[i].temperature() - neighbour_thermal_conductor.temperature();
-                thermal_conductors[i].thermal_conductivity * temp_diff
+            .filter_map(|neighbour_entity| snapshot.get(neighbour_entity))
+            .map(|&(neighbour_temperature, neighbour_conductivity)| {
+                ThermalConductor::conductivity_between(conductivity, neighbour_conductivity)
+                    * (temperature - neighbour_temperature)
             })
             .sum();
 
-        heat_diffs.push(heat_diff);
-    }
-
-    // set all heat at once
-    for (i, (_, mut thermal_conductor)) in query.iter_mut().enumerate() {
-        thermal_conductor.heat -= heat_diffs[i];
+        thermal_conductor.heat -= heat_diff;
     }
 }
 
-fn accumulate_energy_from_solar(mut terrain: Query<&mut ThermalConductor>, sun: Res<Sun>) {
+fn accumulate_energy_from_solar(
+    mut terrain: Query<&mut ThermalConductor>,
+    sun: Res<Sun>,
+    config: Res<SimulationConfig>,
+) {
     for mut thermal_conductor in terrain.iter_mut() {
         thermal_conductor.heat += sun.get_energy_part_for_tile();
 
-        thermal_conductor.heat -= thermal_conductor.get_heat_lose();
+        thermal_conductor.heat -=
+            thermal_conductor.get_heat_lose(config.terrain.heat_loss_per_degree);
 
         thermal_conductor.clamp_heat();
     }
 }
 
-#[derive(Resource)]
-pub struct AssetsMapTemperature {
-    pub medium_materials: HashMap<i32, Handle<StandardMaterial>>,
-    pub default_material_low: Handle<StandardMaterial>,
-    pub default_material_high: Handle<StandardMaterial>,
-    pub selected_material: Handle<StandardMaterial>,
-}
-
-fn initialize_assets_map_temperature(
-    mut cmd: Commands,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    let default_material_low = materials.add(Color::BLACK);
-    let default_material_high = materials.add(Color::linear_rgb(1., 1., 1.));
-    let selected_material = materials.add(Color::linear_rgb(0.2, 0.8, 0.8));
-
-    let mut medium_materials = HashMap::new();
-
-    // create material for every i32 between min and max temperature
-    let mut temp = ThermalConductor::min_temperature();
-    while temp <= ThermalConductor::max_temperature() {
-        medium_materials.insert(
-            temp as i32,
-            materials.add(Color::srgb(
-                (temp - ThermalConductor::min_temperature())
-                    / (ThermalConductor::max_temperature() - ThermalConductor::min_temperature()),
-                0.1,
-                0.2,
-            )),
-        );
-        temp += 1.0;
+fn update_tile_temperatures(mut terrain: Query<(&ThermalConductor, &mut TileTemperature)>) {
+    for (thermal_conductor, mut tile_temperature) in terrain.iter_mut() {
+        tile_temperature.value = thermal_conductor.temperature();
     }
-
-    cmd.insert_resource(AssetsMapTemperature {
-        selected_material,
-        medium_materials,
-        default_material_low,
-        default_material_high,
-    });
 }
+
 fn update_tile_color_for_thermal(
-    mut tiles: Query<(&mut MeshMaterial3d<StandardMaterial>, &ThermalConductor)>,
-    assets_map: Res<AssetsMapTemperature>,
+    tiles: Query<(&Tile, &TileTemperature)>,
+    terrain_texture: Option<Res<TerrainTexture>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
     mut timer: ResMut<ThermalOverlayUpdateTimer>,
+    mut legend: ResMut<OverlayLegend>,
 ) {
     if !timer.tick(Duration::from_secs(1)).just_finished() {
         return;
     }
 
-    for (mut mesh_material, medium) in tiles.iter_mut() {
-        let temp = medium.heat / medium.heat_capacity;
-
-        mesh_material.0 = if temp < ThermalConductor::min_temperature() {
-            assets_map.default_material_low.clone()
-        } else if temp > ThermalConductor::max_temperature() {
-            assets_map.default_material_high.clone()
-        } else {
-            assets_map
-            .medium_materials
-            .get(&(temp as i32))
-            .unwrap_or_else(|| panic!("There should be materials prepared for all int temperatures between min and max temperature. This temp: {}", temp))
-            .clone()
-        }
+    let Some(terrain_texture) = terrain_texture else {
+        return;
+    };
+    let Some(image) = terrain_texture.get_image_mut(&mut images, &mut materials) else {
+        return;
+    };
+
+    let min_temperature = ThermalConductor::min_temperature();
+    let max_temperature = ThermalConductor::max_temperature();
+    let gradient = ColorGradient {
+        from: Color::srgb(0.0, 0.1, 0.2),
+        to: Color::srgb(1.0, 0.1, 0.2),
+    };
+
+    *legend = OverlayLegend::gradient("Temperature", min_temperature, max_temperature, &gradient);
+
+    for (tile, tile_temperature) in tiles.iter() {
+        let normalized_temperature =
+            (tile_temperature.value - min_temperature) / (max_temperature - min_temperature);
+
+        terrain_texture.set_tile_color(image, tile, gradient.get_color(normalized_temperature));
     }
 }
 
@@ -1,8 +1,16 @@
 use std::{cell::RefCell, f32::consts::PI};
 
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+    utils::HashSet,
+};
 use rand::{rngs::ThreadRng, thread_rng, Rng};
 use rand_distr::Uniform;
+use serde::Deserialize;
 
 thread_local! {
     static RNG: RefCell<ThreadRng> = RefCell::new(thread_rng());
@@ -14,97 +22,288 @@ pub struct Tile {
     pub row: u32,
 }
 
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TileShape {
+    Square,
+    /// Pointy-top hexagons, every odd row is shifted right by half of the tile (so called "odd-r" layout).
+    /// All 6 neighbours are in the same distance, so diffusion and movement don't depend on direction.
+    Hexagonal,
+}
+
 #[derive(Resource, Reflect)]
 pub struct TileLayout {
     pub rows: u32,
     pub cols: u32,
+    pub shape: TileShape,
 
+    /// for hexagons it's the distance between 2 parallel sides
     pub tile_size: f32,
     pub half_tile_size: f32,
+    /// vertical distance between centers of tiles in 2 neighbouring rows
+    pub row_height: f32,
     pub width: f32,
     pub height: f32,
 
+    /// if true, edges of the map are connected (left with right, top with bottom), so the world is a torus
+    pub toroidal: bool,
+
+    /// tile entities row by row, use `get_tile_entity` to get entity for coordinates
     #[reflect(ignore)]
-    pub entities: Vec<Vec<Entity>>,
+    pub entities: Vec<Entity>,
 }
 
 impl TileLayout {
-    pub fn new(rows: u32, cols: u32, tile_size: f32) -> Self {
+    pub fn new(rows: u32, cols: u32, shape: TileShape, tile_size: f32, toroidal: bool) -> Self {
+        let (row_height, width, height) = match shape {
+            TileShape::Square => (tile_size, cols as f32 * tile_size, rows as f32 * tile_size),
+            TileShape::Hexagonal => {
+                assert!(
+                    !toroidal || rows.is_multiple_of(2),
+                    "Toroidal hexagonal map needs even number of rows"
+                );
+
+                let hex_radius = tile_size / 3.0f32.sqrt();
+                let row_height = 1.5 * hex_radius;
+
+                // NOTE: toroidal map repeats every `rows * row_height`, otherwise we need space for shifted rows and hexagon tips
+                if toroidal {
+                    (
+                        row_height,
+                        cols as f32 * tile_size,
+                        rows as f32 * row_height,
+                    )
+                } else {
+                    let shifted_rows_margin = if rows > 1 { tile_size / 2.0 } else { 0.0 };
+
+                    (
+                        row_height,
+                        cols as f32 * tile_size + shifted_rows_margin,
+                        rows as f32 * row_height + hex_radius / 2.0,
+                    )
+                }
+            }
+        };
+
         Self {
             rows,
             cols,
+            shape,
 
             tile_size,
             half_tile_size: tile_size / 2.0,
-            width: cols as f32 * tile_size,
-            height: rows as f32 * tile_size,
+            row_height,
+            width,
+            height,
 
-            entities: vec![],
+            toroidal,
+
+            entities: Vec::with_capacity((rows * cols) as usize),
         }
     }
 
-    pub fn add_new_row(&mut self) {
-        self.entities.push(vec![]);
+    /// Radius of the circle circumscribed on a hexagon
+    fn hex_radius(&self) -> f32 {
+        self.tile_size / 3.0f32.sqrt()
+    }
+
+    /// Tiles have to be added row by row, starting from `(0, 0)`
+    pub fn add_tile_entity(&mut self, entity: Entity) {
+        assert!(
+            self.entities.len() < (self.rows * self.cols) as usize,
+            "All tiles have already been added"
+        );
+
+        self.entities.push(entity);
     }
 
-    pub fn add_new_tile_to_last_row(&mut self, entity: Entity) {
-        self.entities
-            .last_mut()
-            .expect("No vector for entities")
-            .push(entity);
+    pub fn get_tile_index(&self, col: u32, row: u32) -> usize {
+        (row * self.cols + col) as usize
+    }
+
+    pub fn get_tile_entity(&self, col: u32, row: u32) -> Entity {
+        self.entities[self.get_tile_index(col, row)]
     }
 
     pub fn get_neighbour_entities(&self, col: u32, row: u32) -> Vec<Entity> {
-        let neighbor_offsets: [(i32, i32); 8] = [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ];
+        let neighbor_offsets: &[(i32, i32)] = match self.shape {
+            TileShape::Square => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+            TileShape::Hexagonal => self.get_hex_neighbour_offsets(row),
+        };
 
         let mut entities = Vec::new();
 
-        for (row_offset, col_offset) in neighbor_offsets.into_iter() {
-            let neighbour_row = (row as i32 + row_offset) as u32;
-            let neighbour_col = (col as i32 + col_offset) as u32;
+        for &(row_offset, col_offset) in neighbor_offsets.iter() {
+            let Some((neighbour_col, neighbour_row)) =
+                self.get_offset_tile_coordinates(col, row, col_offset, row_offset)
+            else {
+                continue;
+            };
 
-            if self.is_tile_in_bounds(neighbour_col, neighbour_row) {
-                entities.push(self.entities[neighbour_row as usize][neighbour_col as usize]);
+            let entity = self.get_tile_entity(neighbour_col, neighbour_row);
+
+            // NOTE: on small toroidal maps the same tile can be a neighbour from more than one side
+            if !entities.contains(&entity) {
+                entities.push(entity);
             }
         }
 
         entities
     }
 
+    /// Coordinates (col, row) of tiles sharing an edge with given tile
+    pub fn get_adjacent_tile_coordinates(&self, col: u32, row: u32) -> Vec<(u32, u32)> {
+        let adjacent_offsets: &[(i32, i32)] = match self.shape {
+            TileShape::Square => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            TileShape::Hexagonal => self.get_hex_neighbour_offsets(row),
+        };
+
+        let mut coordinates = Vec::new();
+
+        for &(row_offset, col_offset) in adjacent_offsets.iter() {
+            if let Some(neighbour) =
+                self.get_offset_tile_coordinates(col, row, col_offset, row_offset)
+            {
+                if neighbour != (col, row) && !coordinates.contains(&neighbour) {
+                    coordinates.push(neighbour);
+                }
+            }
+        }
+
+        coordinates
+    }
+
+    /// Number of tiles sharing an edge with a tile that isn't on the map edge
+    pub fn max_adjacent_tiles(&self) -> usize {
+        match self.shape {
+            TileShape::Square => 4,
+            TileShape::Hexagonal => 6,
+        }
+    }
+
+    /// Offsets (row, col) of all 6 neighbours, they depend on whether the row is shifted or not
+    fn get_hex_neighbour_offsets(&self, row: u32) -> &'static [(i32, i32)] {
+        if row.is_multiple_of(2) {
+            &[(0, -1), (0, 1), (-1, -1), (-1, 0), (1, -1), (1, 0)]
+        } else {
+            &[(0, -1), (0, 1), (-1, 0), (-1, 1), (1, 0), (1, 1)]
+        }
+    }
+
+    /// Coordinates (col, row) of tile moved by offset, wrapped around if the map is toroidal, `None` if outside the map
+    fn get_offset_tile_coordinates(
+        &self,
+        col: u32,
+        row: u32,
+        col_offset: i32,
+        row_offset: i32,
+    ) -> Option<(u32, u32)> {
+        self.try_wrap_tile_coordinates(col as i32 + col_offset, row as i32 + row_offset)
+    }
+
+    /// Coordinates (col, row) wrapped around if the map is toroidal, `None` if outside the map
+    fn try_wrap_tile_coordinates(&self, mut col: i32, mut row: i32) -> Option<(u32, u32)> {
+        if self.toroidal {
+            col = col.rem_euclid(self.cols as i32);
+            row = row.rem_euclid(self.rows as i32);
+        }
+
+        if col < 0 || row < 0 || !self.is_tile_in_bounds(col as u32, row as u32) {
+            return None;
+        }
+
+        Some((col as u32, row as u32))
+    }
+
     pub fn get_tile_entity_for_position(&self, position: impl Into<Vec2>) -> Entity {
         self.try_get_tile_entity_for_position(position)
             .expect("Failed to get entity for position")
     }
 
     pub fn try_get_tile_entity_for_position(&self, position: impl Into<Vec2>) -> Option<Entity> {
-        let pos = position.into();
+        self.try_get_tile_coordinates_for_position(position)
+            .map(|(col, row)| self.get_tile_entity(col, row))
+    }
+
+    /// Coordinates (col, row) of the tile that contains position, `None` if position is outside the map
+    pub fn try_get_tile_coordinates_for_position(
+        &self,
+        position: impl Into<Vec2>,
+    ) -> Option<(u32, u32)> {
+        let pos = self.wrap_position(position);
+
+        match self.shape {
+            TileShape::Square => {
+                if !self.is_x_coordinate_in_bounds(pos.x) || !self.is_y_coordinate_in_bounds(pos.y)
+                {
+                    return None;
+                }
+
+                let mut col = ((pos.x - pos.x % self.tile_size) / self.tile_size) as u32;
+                let mut row = ((pos.y - pos.y % self.tile_size) / self.tile_size) as u32;
 
-        if self.is_position_in_bounds(pos) {
-            let mut col = ((pos.x - pos.x % self.tile_size) / self.tile_size) as usize;
-            let mut row = ((pos.y - pos.y % self.tile_size) / self.tile_size) as usize;
+                // NOTE: make sure that if position is on the border, we treat it as in bounds
+                if row == self.rows {
+                    row -= 1;
+                }
+
+                if col == self.cols {
+                    col -= 1;
+                }
 
-            // NOTE: make sure that if position is on the border, we treat it as in bounds
-            if row as u32 == self.rows {
-                row -= 1;
+                Some((col, row))
             }
+            TileShape::Hexagonal => {
+                let (col, row) = self.get_hex_coordinates_for_position(pos);
 
-            if col as u32 == self.cols {
-                col -= 1;
+                self.try_wrap_tile_coordinates(col, row)
             }
+        }
+    }
 
-            Some(self.entities[row][col])
-        } else {
-            None
+    /// Coordinates (col, row) of hexagon containing position, they can be outside the map.
+    /// More: https://www.redblobgames.com/grids/hexagons/#pixel-to-hex
+    fn get_hex_coordinates_for_position(&self, pos: Vec2) -> (i32, i32) {
+        let hex_radius = self.hex_radius();
+
+        // move position so center of the first tile is in (0, 0)
+        let x = pos.x - self.half_tile_size;
+        let y = pos.y - hex_radius;
+
+        // fractional axial coordinates
+        let q = (3.0f32.sqrt() / 3.0 * x - y / 3.0) / hex_radius;
+        let r = (2.0 / 3.0 * y) / hex_radius;
+        let s = -q - r;
+
+        // round to the nearest hexagon, coordinate with the biggest rounding error is calculated from the other two
+        let mut rounded_q = q.round();
+        let mut rounded_r = r.round();
+        let rounded_s = s.round();
+
+        let q_diff = (rounded_q - q).abs();
+        let r_diff = (rounded_r - r).abs();
+        let s_diff = (rounded_s - s).abs();
+
+        if q_diff > r_diff && q_diff > s_diff {
+            rounded_q = -rounded_r - rounded_s;
+        } else if r_diff > s_diff {
+            rounded_r = -rounded_q - rounded_s;
         }
+
+        // axial to "odd-r" offset coordinates
+        let row = rounded_r as i32;
+        let col = rounded_q as i32 + row.div_euclid(2);
+
+        (col, row)
     }
 
     pub fn get_tile_entity_for_transform(&self, transform: &Transform) -> Entity {
@@ -116,32 +315,94 @@ impl TileLayout {
         self.try_get_tile_entity_for_position(transform.translation.truncate())
     }
 
+    /// All tiles that have at least part of them in the circle
     pub fn get_tile_entities_in_range(&self, position: impl Into<Vec2>, range: f32) -> Vec<Entity> {
         let pos: Vec2 = position.into();
         let mut tile_entities = Vec::new();
 
-        // get bounds of square around circle
-        let min_x = pos.x - range;
-        let max_x = pos.x + range;
-        let min_y = pos.y - range;
-        let max_y = pos.y + range;
-
-        let mut x = min_x;
-        let mut y = min_y;
-        while x <= max_x {
-            while y <= max_y {
-                if let Some(tile_entity) = self.try_get_tile_entity_for_position(pos) {
+        // get bounds (in tile coordinates) of square around circle, with margin for shifted hexagon rows
+        let min_col = ((pos.x - range) / self.tile_size).floor() as i32 - 1;
+        let max_col = ((pos.x + range) / self.tile_size).ceil() as i32 + 1;
+        let min_row = ((pos.y - range) / self.row_height).floor() as i32 - 1;
+        let max_row = ((pos.y + range) / self.row_height).ceil() as i32 + 1;
+
+        // NOTE: on toroidal map the same tile can be reached from both sides when the square is bigger than the map
+        let can_repeat = self.toroidal
+            && (max_col - min_col >= self.cols as i32 || max_row - min_row >= self.rows as i32);
+        let mut seen_tile_entities = HashSet::new();
+
+        for row in min_row..=max_row {
+            for col in min_col..=max_col {
+                let Some((col, row)) = self.try_wrap_tile_coordinates(col, row) else {
+                    continue;
+                };
+
+                let tile_position = self.get_tile_position(&Tile { col, row });
+                if self.get_distance(pos, tile_position) > range + self.get_tile_circumradius() {
+                    continue;
+                }
+
+                let tile_entity = self.get_tile_entity(col, row);
+                if !can_repeat || seen_tile_entities.insert(tile_entity) {
                     tile_entities.push(tile_entity);
                 }
+            }
+        }
 
-                y += self.tile_size;
+        tile_entities
+    }
+
+    /// Tiles exactly `ring` steps away (in tile coordinates) from the tile under the position, ring `0` is that tile.
+    /// Empty if the position isn't on the map.
+    pub fn get_tile_entities_in_ring(&self, position: impl Into<Vec2>, ring: u32) -> Vec<Entity> {
+        let Some((center_col, center_row)) = self.try_get_tile_coordinates_for_position(position)
+        else {
+            return Vec::new();
+        };
+        let (center_col, center_row, ring) = (center_col as i32, center_row as i32, ring as i32);
+        let mut tile_entities = Vec::new();
+
+        for row in (center_row - ring)..=(center_row + ring) {
+            // NOTE: rows in between only have their first and last tile in the ring
+            let is_edge_row = (row - center_row).abs() == ring;
+            let col_step = if is_edge_row { 1 } else { 2 * ring as usize };
+
+            for col in ((center_col - ring)..=(center_col + ring)).step_by(col_step) {
+                if let Some((col, row)) = self.try_wrap_tile_coordinates(col, row) {
+                    tile_entities.push(self.get_tile_entity(col, row));
+                }
             }
-            x += self.tile_size;
         }
 
         tile_entities
     }
 
+    /// Lower bound of the distance from any position in the center tile to any position in a tile `ring` steps away
+    pub fn get_min_distance_to_ring(&self, ring: u32) -> f32 {
+        if ring == 0 {
+            return 0.0;
+        }
+
+        // NOTE: hexagonal rows are shifted by half a tile, so tiles in neighbouring columns can be closer
+        let ring = ring as f32;
+        let min_center_distance = (ring * self.row_height).min((ring - 0.5) * self.tile_size);
+
+        (min_center_distance - 2.0 * self.get_tile_circumradius()).max(0.0)
+    }
+
+    /// Rings further than this don't contain any new tiles
+    pub fn get_max_ring(&self) -> u32 {
+        self.cols.max(self.rows)
+    }
+
+    /// Radius of the smallest circle containing whole tile
+    pub fn get_tile_circumradius(&self) -> f32 {
+        match self.shape {
+            TileShape::Square => self.half_tile_size * 2.0f32.sqrt(),
+            TileShape::Hexagonal => self.hex_radius(),
+        }
+    }
+
     pub fn is_tile_in_bounds(&self, col: u32, row: u32) -> bool {
         row < self.rows && col < self.cols
     }
@@ -149,20 +410,58 @@ impl TileLayout {
     pub fn is_position_in_bounds(&self, position: impl Into<Vec2>) -> bool {
         let pos: Vec2 = position.into();
 
-        self.is_x_coordinate_in_bounds(pos.x) && self.is_y_coordinate_in_bounds(pos.y)
+        match self.shape {
+            TileShape::Square => {
+                self.is_x_coordinate_in_bounds(pos.x) && self.is_y_coordinate_in_bounds(pos.y)
+            }
+            // NOTE: hexagonal map has jagged edges, so not every position inside width and height is on some tile
+            TileShape::Hexagonal => self.try_get_tile_coordinates_for_position(pos).is_some(),
+        }
     }
 
+    /// NOTE: every coordinate is in bounds on toroidal map, it just needs to be wrapped
     pub fn is_x_coordinate_in_bounds(&self, x: f32) -> bool {
-        x >= 0.0 && x <= self.width
+        self.toroidal || (x >= 0.0 && x <= self.width)
     }
 
     pub fn is_y_coordinate_in_bounds(&self, y: f32) -> bool {
-        y >= 0.0 && y <= self.height
+        self.toroidal || (y >= 0.0 && y <= self.height)
+    }
+
+    /// Moves position back on the map if the map is toroidal, returns position unchanged otherwise
+    pub fn wrap_position(&self, position: impl Into<Vec2>) -> Vec2 {
+        let pos: Vec2 = position.into();
+
+        if !self.toroidal {
+            return pos;
+        }
+
+        Vec2::new(pos.x.rem_euclid(self.width), pos.y.rem_euclid(self.height))
+    }
+
+    /// Shortest vector from one position to another, on toroidal map it can go through map edges
+    pub fn get_displacement(&self, from: impl Into<Vec2>, to: impl Into<Vec2>) -> Vec2 {
+        let mut diff = to.into() - from.into();
+
+        if self.toroidal {
+            diff.x -= self.width * (diff.x / self.width).round();
+            diff.y -= self.height * (diff.y / self.height).round();
+        }
+
+        diff
+    }
+
+    pub fn get_distance(&self, from: impl Into<Vec2>, to: impl Into<Vec2>) -> f32 {
+        self.get_displacement(from, to).length()
     }
 
     pub fn is_position_inside_tile(&self, position: impl Into<Vec2>, tile: &Tile) -> bool {
         let pos = position.into();
 
+        if self.shape == TileShape::Hexagonal {
+            return self.try_get_tile_coordinates_for_position(pos) == Some((tile.col, tile.row));
+        }
+
         let (tile_min, tile_max) = self.get_tile_bounds(tile);
 
         if pos.x < tile_min.x || pos.x > tile_max.x {
@@ -177,13 +476,35 @@ impl TileLayout {
     }
 
     pub fn get_tile_position(&self, tile: &Tile) -> Vec2 {
-        Vec2 {
-            x: tile.col as f32 * self.tile_size + self.half_tile_size,
-            y: tile.row as f32 * self.tile_size + self.half_tile_size,
+        match self.shape {
+            TileShape::Square => Vec2 {
+                x: tile.col as f32 * self.tile_size + self.half_tile_size,
+                y: tile.row as f32 * self.tile_size + self.half_tile_size,
+            },
+            TileShape::Hexagonal => {
+                let row_shift = if tile.row % 2 == 1 {
+                    self.half_tile_size
+                } else {
+                    0.0
+                };
+
+                Vec2 {
+                    x: tile.col as f32 * self.tile_size + self.half_tile_size + row_shift,
+                    y: tile.row as f32 * self.row_height + self.hex_radius(),
+                }
+            }
         }
     }
 
+    /// Bounding box of the tile
     pub fn get_tile_bounds(&self, tile: &Tile) -> (Vec2, Vec2) {
+        if self.shape == TileShape::Hexagonal {
+            let center = self.get_tile_position(tile);
+            let half_size = Vec2::new(self.half_tile_size, self.hex_radius());
+
+            return (center - half_size, center + half_size);
+        }
+
         let min = Vec2 {
             x: tile.col as f32 * self.tile_size,
             y: tile.row as f32 * self.tile_size,
@@ -200,24 +521,24 @@ impl TileLayout {
     pub fn get_random_position_in_tile(&self, tile: &Tile) -> Vec2 {
         let (pos_min, pos_max) = self.get_tile_bounds(tile);
 
-        RNG.with(|rng| {
-            let mut rng = rng.borrow_mut();
+        // NOTE: bounds of hexagon are bigger than hexagon itself, so we try until position is inside
+        loop {
+            let position = RNG.with(|rng| {
+                let mut rng = rng.borrow_mut();
 
-            let x = rng.gen_range(pos_min.x..pos_max.x);
-            let y = rng.gen_range(pos_min.y..pos_max.y);
+                let x = rng.gen_range(pos_min.x..pos_max.x);
+                let y = rng.gen_range(pos_min.y..pos_max.y);
 
-            Vec2::new(x, y)
-        })
-    }
+                Vec2::new(x, y)
+            });
 
-    pub fn get_random_position_in_ring(
-        &self,
-        position: impl Into<Vec2>,
-        range: f32,
-        inner_range: f32,
-    ) -> Vec2 {
-        let pos = position.into();
+            if self.shape == TileShape::Square || self.is_position_inside_tile(position, tile) {
+                return self.wrap_position(position);
+            }
+        }
+    }
 
+    fn get_random_offset_in_ring(range: f32, inner_range: f32) -> Vec2 {
         let (r, theta) = RNG.with(|rng| {
             let mut rng = rng.borrow_mut();
 
@@ -227,14 +548,25 @@ impl TileLayout {
             (r, theta)
         });
 
-        let x_diff = r * theta.cos();
-        let y_diff = r * theta.sin();
+        Vec2::new(r * theta.cos(), r * theta.sin())
+    }
+
+    /// Random position in the ring, moved back inside the map if it lands outside of it
+    pub fn get_random_position_in_ring(
+        &self,
+        position: impl Into<Vec2>,
+        range: f32,
+        inner_range: f32,
+    ) -> Vec2 {
+        let position_possibly_outside_bounds =
+            position.into() + Self::get_random_offset_in_ring(range, inner_range);
 
-        let x_possibly_outside_bounds = pos.x + x_diff;
-        let y_possibly_outside_bounds = pos.y + y_diff;
+        if self.toroidal {
+            return self.wrap_position(position_possibly_outside_bounds);
+        }
 
-        let mut x = x_possibly_outside_bounds.clamp(0.0, self.width);
-        let mut y = y_possibly_outside_bounds.clamp(0.0, self.height);
+        let mut x = position_possibly_outside_bounds.x.clamp(0.0, self.width);
+        let mut y = position_possibly_outside_bounds.y.clamp(0.0, self.height);
 
         // NOTE: we move back position in case it's "smashed" against map bounds
         if x == 0.0 {
@@ -250,46 +582,94 @@ impl TileLayout {
             y -= self.half_tile_size;
         }
 
-        Vec2::new(x, y)
+        let position = Vec2::new(x, y);
+
+        // NOTE: position near the jagged edge of hexagonal map can still be outside any tile, then we use center of the nearest one
+        if !self.is_position_in_bounds(position) {
+            let (col, row) = self.get_hex_coordinates_for_position(position);
+
+            return self.get_tile_position(&Tile {
+                col: col.clamp(0, self.cols as i32 - 1) as u32,
+                row: row.clamp(0, self.rows as i32 - 1) as u32,
+            });
+        }
+
+        position
+    }
+
+    /// Random position in the ring, `None` if it lands outside of the map
+    pub fn try_get_random_position_in_ring(
+        &self,
+        position: impl Into<Vec2>,
+        range: f32,
+        inner_range: f32,
+    ) -> Option<Vec2> {
+        let position = self
+            .wrap_position(position.into() + Self::get_random_offset_in_ring(range, inner_range));
+
+        self.is_position_in_bounds(position).then_some(position)
     }
 
     pub fn get_random_position_in_range(&self, position: impl Into<Vec2>, range: f32) -> Vec2 {
         self.get_random_position_in_ring(position, range, 0.0)
     }
 
+    /// One mesh for the whole map. Every tile is a separate polygon and all its vertices point at the same texel,
+    /// so texture with `cols x rows` pixels (one per tile) can be used to color tiles.
     pub fn generate_mesh(&self) -> Mesh {
-        Cuboid::new(1.0, 1.0, 0.00001).into()
-
-        // NOTE: this standard Cuboid works better with lighting, custom mesh would probably be more performant, but it's not important for now
-
-        // let mut mesh = Mesh::new(
-        //     PrimitiveTopology::TriangleList,
-        //     // NOTE: RENDER_WORLD for rendering, MAIN_WORLD for bevy_picking
-        //     RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
-        // );
-
-        // let side_of_the_square = 1.0;
-        // let x = side_of_the_square / 2.0;
-
-        // let vertexes: Vec<[f32; 3]> = vec![
-        //     [-x, x, 0.],  // 0 top left
-        //     [x, x, 0.],   // 1 top right
-        //     [-x, -x, 0.], // 2 bottom left
-        //     [x, -x, 0.],  // 3 bottom right
-        // ];
-
-        // let indices = vec![
-        //     2, 1, 0, // top left
-        //     2, 3, 1, // bottom right
-        // ];
-        // let normals: Vec<[f32; 3]> = [[0., 1., 0.]].repeat(vertexes.len());
-        // let uvs: Vec<[f32; 2]> = (0..vertexes.len()).map(|_| [0., 0.]).collect();
-
-        // mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertexes);
-        // mesh.insert_indices(Indices::U32(indices));
-        // mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        // mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-
-        // mesh
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            // NOTE: RENDER_WORLD for rendering, MAIN_WORLD for bevy_picking
+            RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+        );
+
+        let corners: Vec<Vec2> = match self.shape {
+            TileShape::Square => vec![
+                Vec2::new(-self.half_tile_size, -self.half_tile_size),
+                Vec2::new(self.half_tile_size, -self.half_tile_size),
+                Vec2::new(self.half_tile_size, self.half_tile_size),
+                Vec2::new(-self.half_tile_size, self.half_tile_size),
+            ],
+            // NOTE: pointy-top, so first corner is at 30 degrees
+            TileShape::Hexagonal => (0..6)
+                .map(|i| Vec2::from_angle(PI / 6.0 + i as f32 * PI / 3.0) * self.hex_radius())
+                .collect(),
+        };
+
+        let tiles_count = (self.rows * self.cols) as usize;
+        let mut vertexes: Vec<[f32; 3]> = Vec::with_capacity(tiles_count * corners.len());
+        let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(tiles_count * corners.len());
+        let mut indices: Vec<u32> = Vec::with_capacity(tiles_count * (corners.len() - 2) * 3);
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let center = self.get_tile_position(&Tile { col, row });
+                let uv = [
+                    (col as f32 + 0.5) / self.cols as f32,
+                    (row as f32 + 0.5) / self.rows as f32,
+                ];
+                let first_index = vertexes.len() as u32;
+
+                for corner in corners.iter() {
+                    let vertex = center + *corner;
+                    vertexes.push([vertex.x, vertex.y, 0.0]);
+                    uvs.push(uv);
+                }
+
+                // triangle fan, corners are counter-clockwise so triangles are facing up
+                for i in 1..(corners.len() as u32 - 1) {
+                    indices.extend([first_index, first_index + i, first_index + i + 1]);
+                }
+            }
+        }
+
+        let normals: Vec<[f32; 3]> = [[0., 0., 1.]].repeat(vertexes.len());
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertexes);
+        mesh.insert_indices(Indices::U32(indices));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+
+        mesh
     }
 }
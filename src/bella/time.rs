@@ -23,8 +23,10 @@ impl Plugin for TimePlugin {
                 PreUpdate,
                 (
                     send_time_passed_events_if_needed,
-                    send_day_passed_event_if_needed,
+                    // NOTE: DayTimer counts time units, not frames
+                    send_day_passed_event_if_needed.run_if(on_event::<TimeUnitPassedEvent>),
                 )
+                    .chain()
                     .run_if(in_state(PauseState::Running)),
             );
     }
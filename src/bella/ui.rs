@@ -1,8 +1,10 @@
 pub mod camera;
+pub mod terrain_editor;
 
 use bevy::prelude::*;
 
 use self::camera::MyCameraPlugin;
+use self::terrain_editor::TerrainEditorPlugin;
 
 use super::time::{SimulationTime, TimeUnitPassedEvent};
 
@@ -11,6 +13,7 @@ pub struct UiPlugin;
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(MyCameraPlugin)
+            .add_plugins(TerrainEditorPlugin)
             .add_systems(Startup, setup_timer_ui)
             .add_systems(
                 PostUpdate,
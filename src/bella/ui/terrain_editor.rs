@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{egui, EguiContexts};
+
+use crate::bella::{
+    config::SimulationConfig,
+    restart::SimulationState,
+    terrain::{
+        terrain_file::SaveTerrainEvent,
+        tile::{Tile, TileLayout},
+        BiomeType, ChangeBiomeEvent, Humidity, Nutrients,
+    },
+    ui_facade::{EguiFocusState, EguiVisibleState, TerrainEditorState},
+};
+
+pub struct TerrainEditorPlugin;
+
+impl Plugin for TerrainEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerrainBrush>()
+            .add_systems(
+                Update,
+                change_terrain_editor_state_based_on_keyboard_input
+                    .run_if(in_state(EguiFocusState::IsNotFocused)),
+            )
+            .add_systems(
+                Update,
+                paint_terrain
+                    .run_if(in_state(TerrainEditorState::On))
+                    .run_if(in_state(EguiFocusState::IsNotFocused))
+                    .run_if(in_state(SimulationState::Simulation)),
+            )
+            .add_systems(
+                Update,
+                terrain_editor_ui
+                    .run_if(in_state(TerrainEditorState::On))
+                    .run_if(in_state(EguiVisibleState::Yes))
+                    .run_if(in_state(SimulationState::Simulation)),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrushMode {
+    Biome,
+    Humidity,
+    Nutrients,
+}
+
+/// What is painted on tiles while left mouse button is held in the terrain editor
+#[derive(Resource, Debug)]
+pub struct TerrainBrush {
+    pub mode: BrushMode,
+    pub biome: BiomeType,
+    pub humidity: f32,
+    pub nutrients: f32,
+    /// radius of the brush in tiles, `0` paints only the tile under the cursor
+    pub size: u32,
+    pub save_path: String,
+}
+
+impl Default for TerrainBrush {
+    fn default() -> Self {
+        Self {
+            mode: BrushMode::Biome,
+            biome: BiomeType::Water,
+            humidity: 1.0,
+            nutrients: 0.0,
+            size: 1,
+            save_path: "terrain.msgpack".to_string(),
+        }
+    }
+}
+
+fn change_terrain_editor_state_based_on_keyboard_input(
+    current_state: Res<State<TerrainEditorState>>,
+    mut next_state: ResMut<NextState<TerrainEditorState>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyB) {
+        next_state.set(match **current_state {
+            TerrainEditorState::On => TerrainEditorState::Off,
+            TerrainEditorState::Off => TerrainEditorState::On,
+        });
+    }
+}
+
+fn paint_terrain(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    brush: Res<TerrainBrush>,
+    tile_layout: Res<TileLayout>,
+    mut tiles: Query<(&Tile, &BiomeType, &mut Humidity, &mut Nutrients)>,
+    mut change_biome_event_writer: EventWriter<ChangeBiomeEvent>,
+) {
+    if !mouse_button.pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Some(cursor_position) = window.cursor_position() else {
+        return;
+    };
+
+    let (camera, camera_transform) = camera.into_inner();
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else {
+        return;
+    };
+
+    // NOTE: camera is angled, so we need to find where the ray hits the terrain plane
+    let Some(distance) = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Z)) else {
+        return;
+    };
+    let position = ray.get_point(distance).truncate();
+
+    let Some(tile_under_cursor) = tile_layout.try_get_tile_entity_for_position(position) else {
+        return;
+    };
+
+    let brush_range = brush.size as f32 * tile_layout.tile_size;
+
+    for tile_entity in tile_layout.get_tile_entities_in_range(position, brush_range) {
+        let Ok((tile, biome, mut humidity, mut nutrients)) = tiles.get_mut(tile_entity) else {
+            continue;
+        };
+
+        let tile_position = tile_layout.get_tile_position(tile);
+        if tile_entity != tile_under_cursor
+            && tile_layout.get_distance(position, tile_position) > brush_range
+        {
+            continue;
+        }
+
+        match brush.mode {
+            BrushMode::Biome => {
+                if *biome != brush.biome {
+                    change_biome_event_writer.send(ChangeBiomeEvent {
+                        tile_entity,
+                        biome: brush.biome,
+                    });
+                }
+            }
+            BrushMode::Humidity => humidity.value = brush.humidity,
+            BrushMode::Nutrients => nutrients.set_base_value(brush.nutrients),
+        }
+    }
+}
+
+fn terrain_editor_ui(
+    mut contexts: EguiContexts,
+    mut brush: ResMut<TerrainBrush>,
+    mut save_terrain_event_writer: EventWriter<SaveTerrainEvent>,
+    config: Res<SimulationConfig>,
+) {
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+
+    egui::Window::new("Terrain editor")
+        .default_pos((0.0, 105.0))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut brush.mode, BrushMode::Biome, "Biome");
+                ui.radio_value(&mut brush.mode, BrushMode::Humidity, "Humidity");
+                ui.radio_value(&mut brush.mode, BrushMode::Nutrients, "Nutrients");
+            });
+
+            match brush.mode {
+                BrushMode::Biome => {
+                    egui::ComboBox::from_label("Biome")
+                        .selected_text(format!("{:?}", brush.biome))
+                        .show_ui(ui, |ui| {
+                            for biome in BiomeType::ALL {
+                                ui.selectable_value(
+                                    &mut brush.biome,
+                                    biome,
+                                    format!("{:?}", biome),
+                                );
+                            }
+                        });
+                }
+                BrushMode::Humidity => {
+                    ui.add(
+                        egui::Slider::new(
+                            &mut brush.humidity,
+                            0.0..=config.environment.water_humidity,
+                        )
+                        .text("Humidity"),
+                    );
+                }
+                BrushMode::Nutrients => {
+                    ui.add(
+                        egui::DragValue::new(&mut brush.nutrients)
+                            .speed(0.1)
+                            .prefix("Nutrients: "),
+                    );
+                }
+            }
+
+            ui.add(egui::Slider::new(&mut brush.size, 0..=20).text("Brush size"));
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut brush.save_path);
+
+                if ui.button("Save terrain").clicked() {
+                    save_terrain_event_writer.send(SaveTerrainEvent {
+                        path: PathBuf::from(&brush.save_path),
+                    });
+                }
+            });
+
+            ui.label("Hold left mouse button to paint, press B to close the editor");
+        });
+}
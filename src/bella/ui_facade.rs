@@ -1,12 +1,17 @@
 use bevy::prelude::*;
 
+#[cfg(not(feature = "bella_headless"))]
+use super::terrain::tile::TileLayout;
+
 pub struct UiFacadePlugin;
 
 impl Plugin for UiFacadePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<EguiFocusState>()
             .init_state::<EguiVisibleState>()
-            .insert_resource(ChosenEntity { entity: None });
+            .init_state::<TerrainEditorState>()
+            .insert_resource(ChosenEntity { entity: None })
+            .insert_resource(ChosenTile { entity: None });
     }
 }
 
@@ -24,21 +29,40 @@ pub enum EguiFocusState {
     IsFocused,
 }
 
+/// When terrain editor is on, left mouse button paints tiles instead of choosing entities
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum TerrainEditorState {
+    #[default]
+    Off,
+    On,
+}
+
 #[derive(Resource)]
 pub struct ChosenEntity {
     pub entity: Option<Entity>,
 }
 
+/// Tile chosen by clicking on the terrain, shown in the tile inspector
+#[derive(Resource)]
+pub struct ChosenTile {
+    pub entity: Option<Entity>,
+}
+
 #[cfg(not(feature = "bella_headless"))]
 pub fn choose_entity_observer(
     click: Trigger<Pointer<Click>>,
     mut chosen_entity: ResMut<ChosenEntity>,
     egui_focus_state: Res<State<EguiFocusState>>,
+    terrain_editor_state: Res<State<TerrainEditorState>>,
 ) {
     if matches!(**egui_focus_state, EguiFocusState::IsFocused) {
         return;
     }
 
+    if matches!(**terrain_editor_state, TerrainEditorState::On) {
+        return;
+    }
+
     if click.button != PointerButton::Primary {
         return;
     }
@@ -48,3 +72,36 @@ pub fn choose_entity_observer(
 
 #[cfg(feature = "bella_headless")]
 pub fn choose_entity_observer(click: Trigger<Pointer<Click>>) {}
+
+/// Terrain is a single mesh, so the tile is found from the position of the click
+#[cfg(not(feature = "bella_headless"))]
+pub fn choose_tile_observer(
+    click: Trigger<Pointer<Click>>,
+    mut chosen_tile: ResMut<ChosenTile>,
+    tile_layout: Res<TileLayout>,
+    egui_focus_state: Res<State<EguiFocusState>>,
+    terrain_editor_state: Res<State<TerrainEditorState>>,
+) {
+    if matches!(**egui_focus_state, EguiFocusState::IsFocused) {
+        return;
+    }
+
+    if matches!(**terrain_editor_state, TerrainEditorState::On) {
+        return;
+    }
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(position) = click.hit.position else {
+        return;
+    };
+
+    if let Some(tile_entity) = tile_layout.try_get_tile_entity_for_position(position.truncate()) {
+        chosen_tile.entity = Some(tile_entity);
+    }
+}
+
+#[cfg(feature = "bella_headless")]
+pub fn choose_tile_observer(_click: Trigger<Pointer<Click>>) {}